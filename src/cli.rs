@@ -3,11 +3,9 @@
 
 use clap::{Arg, Command};
 
-use trade_lib::kis;
+use trade_lib::kis::{self, KisResult};
 
-type MyResult<T> = Result<T, Box<dyn std::error::Error>>;
-
-pub fn get_args() -> MyResult<kis::AccountConfig> {
+pub fn get_args() -> KisResult<kis::AccountConfig> {
     let matches = Command::new(env!("CARGO_PKG_NAME"))
         .version(env!("CARGO_PKG_VERSION"))
         .author(format!("\tby {}", env!("CARGO_PKG_AUTHORS").replace(":", ", ")).as_str())
@@ -37,7 +35,7 @@ pub fn get_args() -> MyResult<kis::AccountConfig> {
     // Err("err".into())
 }
 
-pub fn run(config: kis::AccountConfig) -> MyResult<()> {
+pub fn run(config: kis::AccountConfig) -> KisResult<()> {
     println!("{config:#?}");
     // TODO: Trade
     Ok(())
@@ -1,22 +1,29 @@
-type AccountResult<T> = Result<T, Box<dyn std::error::Error>>;
-
 use std::fs;
 use std::io;
+use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
 
+use super::{Error, KisResult};
+
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct AccountConfig {
     id: String,
     // password: String,
     real: bool,
     key: String,
+    /// 종합계좌번호 앞 8자리 (CANO), "50067252-01" 처럼 10자리로 적어도 됨
     account: String,
+    /// 계좌상품코드 뒤 2자리 (ACNT_PRDT_CD)
+    #[serde(default = "default_product_code")]
+    product_code: String,
     phone: String,
     url: String,
     ops: String,
     secret: String,
-    token: String,
+    /// 설정 파일 경로, token 캐시 파일 위치를 정하는데 사용
+    #[serde(skip)]
+    config_path: PathBuf,
 }
 
 impl AccountConfig {
@@ -27,11 +34,12 @@ impl AccountConfig {
             real: false,
             key: "".to_string(),
             account: "".to_string(),
+            product_code: default_product_code(),
             phone: "".to_string(),
             url: "".to_string(),
             ops: "".to_string(),
             secret: "".to_string(),
-            token: "".to_string(),
+            config_path: PathBuf::new(),
         }
     }
 
@@ -39,6 +47,11 @@ impl AccountConfig {
         self.real
     }
 
+    /// HTS ID, 실시간 체결통보 구독에 사용
+    pub fn get_hts_id(&self) -> &str {
+        &self.id
+    }
+
     pub fn get_apikey(&self) -> &str {
         &self.key
     }
@@ -51,33 +64,191 @@ impl AccountConfig {
         self.url.clone()
     }
 
-    pub fn get_access_token(&self) -> &str {
-        &self.token
+    /// 실시간 (웹소켓) 접속 주소, 설정 파일에 없으면 실전/모의 기본 주소
+    pub fn get_ws_url(&self) -> String {
+        match (self.ops.is_empty(), self.real) {
+            (false, _) => self.ops.clone(),
+            (true, true) => "ws://ops.koreainvestment.com:21000".to_string(),
+            (true, false) => "ws://ops.koreainvestment.com:31000".to_string(),
+        }
+    }
+
+    /// 설정 파일 옆에 저장하는 token 캐시 경로 (kis_test.json -> kis_test_token.json)
+    pub fn token_cache_path(&self) -> Option<PathBuf> {
+        self.cache_path("token")
     }
 
-    pub fn set_access_token(&mut self, token: &str) {
-        self.token = token.to_string();
+    /// 설정 파일 옆에 저장하는 websocket 접속키 캐시 경로 (kis_test.json -> kis_test_approval.json)
+    pub fn approval_key_cache_path(&self) -> Option<PathBuf> {
+        self.cache_path("approval")
     }
 
-    pub fn is_acces_token_valid(&self) -> bool {
-        //TODO: check if expired
-        self.token != ""
+    fn cache_path(&self, kind: &str) -> Option<PathBuf> {
+        let stem = self.config_path.file_stem()?.to_str()?;
+        Some(
+            self.config_path
+                .with_file_name(format!("{stem}_{kind}.json")),
+        )
     }
 
     pub(crate) fn get_account_no(&self) -> &str {
         &self.account
     }
+
+    pub(crate) fn get_account_product_code(&self) -> &str {
+        &self.product_code
+    }
+
+    /// mock server 에 접속하는 테스트용 설정
+    #[cfg(test)]
+    pub(crate) fn mock(url: &str, real: bool) -> Self {
+        AccountConfig {
+            real,
+            key: "mock-appkey".to_string(),
+            account: "50067252".to_string(),
+            url: url.to_string(),
+            secret: "mock-secret".to_string(),
+            ..Self::new()
+        }
+    }
+
+    /// 계좌번호 검증, 10자리 계좌번호는 CANO 와 상품코드로 분리
+    fn parse_account(&mut self) -> KisResult<()> {
+        let digits: String = self
+            .account
+            .chars()
+            .filter(|c| *c != '-' && !c.is_whitespace())
+            .collect();
+
+        if !digits.chars().all(|c| c.is_ascii_digit()) {
+            return Err(Error::Config(format!(
+                "invalid account number: {}",
+                self.account
+            )));
+        }
+
+        match digits.len() {
+            8 => self.account = digits,
+            10 => {
+                self.product_code = digits[8..].to_string();
+                self.account = digits[..8].to_string();
+            }
+            _ => {
+                return Err(Error::Config(format!(
+                    "account number must be 8 or 10 digits: {}",
+                    self.account
+                )))
+            }
+        }
+
+        if self.product_code.len() != 2 || !self.product_code.chars().all(|c| c.is_ascii_digit()) {
+            return Err(Error::Config(format!(
+                "invalid account product code: {}",
+                self.product_code
+            )));
+        }
+
+        Ok(())
+    }
 }
 
-pub fn load_account_config(path: &str, real: bool) -> AccountResult<AccountConfig> {
+fn default_product_code() -> String {
+    "01".to_string()
+}
+
+impl Default for AccountConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn load_account_config(path: &str, real: bool) -> KisResult<AccountConfig> {
     let config_path = if real {
         format!("./{path}/kis_real.json")
     } else {
         format!("./{path}/kis_test.json")
     };
 
-    let reader = io::BufReader::new(fs::File::open(config_path)?);
-    let conf: AccountConfig = serde_json::from_reader(reader)?;
+    let file =
+        fs::File::open(&config_path).map_err(|e| Error::Config(format!("{config_path}: {e}")))?;
+    let reader = io::BufReader::new(file);
+    let mut conf: AccountConfig = serde_json::from_reader(reader)
+        .map_err(|e| Error::Config(format!("{config_path}: {e}")))?;
+    // parse_account 의 Error::Config 에는 경로만 덧붙임
+    conf.parse_account().map_err(|e| match e {
+        Error::Config(msg) => Error::Config(format!("{config_path}: {msg}")),
+        e => e,
+    })?;
+    conf.config_path = PathBuf::from(config_path);
 
     Ok(conf)
 }
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+
+    fn config_with_account(account: &str, product_code: Option<&str>) -> AccountConfig {
+        let mut v = serde_json::to_value(AccountConfig::new()).unwrap();
+        v["account"] = account.into();
+        match product_code {
+            Some(code) => v["product_code"] = code.into(),
+            None => {
+                v.as_object_mut().unwrap().remove("product_code");
+            }
+        }
+        serde_json::from_value(v).unwrap()
+    }
+
+    #[test]
+    fn test_parse_account_8_digits() {
+        let mut conf = config_with_account("50067252", None);
+        assert!(conf.parse_account().is_ok());
+        assert_eq!(conf.get_account_no(), "50067252");
+        assert_eq!(conf.get_account_product_code(), "01");
+
+        let mut conf = config_with_account("50067252", Some("22"));
+        assert!(conf.parse_account().is_ok());
+        assert_eq!(conf.get_account_product_code(), "22");
+    }
+
+    #[test]
+    fn test_parse_account_10_digits() {
+        for account in ["50067252-03", "5006725203"] {
+            let mut conf = config_with_account(account, None);
+            assert!(conf.parse_account().is_ok());
+            assert_eq!(conf.get_account_no(), "50067252");
+            assert_eq!(conf.get_account_product_code(), "03");
+        }
+    }
+
+    #[test]
+    fn test_parse_account_invalid() {
+        for account in ["", "5006725", "50067252-0", "5006725a01"] {
+            let mut conf = config_with_account(account, None);
+            assert!(matches!(conf.parse_account(), Err(Error::Config(_))));
+        }
+        let mut conf = config_with_account("50067252", Some("1"));
+        assert!(matches!(conf.parse_account(), Err(Error::Config(_))));
+    }
+
+    #[test]
+    fn test_load_account_config_error() {
+        let dir = format!("target/account_config_{}", std::process::id());
+        fs::create_dir_all(&dir).unwrap();
+        let config_path = format!("./{dir}/kis_test.json");
+        let mut v = serde_json::to_value(AccountConfig::new()).unwrap();
+        v["account"] = "5006725".into();
+        fs::write(&config_path, v.to_string()).unwrap();
+
+        let err = load_account_config(&dir, false).unwrap_err();
+        fs::remove_dir_all(&dir).unwrap();
+        match err {
+            Error::Config(msg) => assert_eq!(
+                msg,
+                format!("{config_path}: account number must be 8 or 10 digits: 5006725")
+            ),
+            e => panic!("unexpected error: {e}"),
+        }
+    }
+}
@@ -1,132 +1,240 @@
 use reqwest::blocking;
-use reqwest::header::{self, HeaderName, HeaderValue};
 use std::collections::HashMap;
-
-use super::AccountConfig;
-use super::{KisRequest, RequestType};
-
-type KisResult<T> = Result<T, Box<dyn std::error::Error>>;
+use std::sync::{Arc, RwLock};
+use std::thread;
+
+use super::client::{self, configure_client, Call, TokenKind};
+use super::endpoint::Endpoint;
+use super::order::{CreditType, OrderAck, OrderQuantity, OrderRequest, OrderSide, OrderType};
+use super::quotation::{
+    BidAskPrices, Conclusion, CurrentPrice, DailyPrice, InvestorTrading, MemberTrading,
+};
+use super::response::{merge_page, parse_response, KisResponse};
+use super::retry::{OrderRetry, Retry};
+use super::{AccessToken, AccountBalance, AccountConfig, BuyingPower, Execution, ExecutionQuery};
+use super::{
+    DailyProfit, ProfitQuery, ProfitReport, Reservation, ReservationAck, ReservationOrder,
+    TradeProfit,
+};
+use super::{KisRequest, KisResult, Priority, RateLimiter, RequestType, RetryPolicy};
+
+pub use super::client::{CONNECT_TIMEOUT, POOL_IDLE_TIMEOUT, REQUEST_TIMEOUT, USER_AGENT};
 
 pub struct KisApi {
     account_info: AccountConfig,
+    client: blocking::Client,
+    access_token: RwLock<AccessToken>,
+    approval_key: RwLock<AccessToken>,
+    rate_limiter: Arc<RateLimiter>,
+    retry_policy: RetryPolicy,
 }
 
 impl KisApi {
+    /// 기본 설정의 HTTP client 로 생성
+    ///
+    /// # Panics
+    /// TLS backend 초기화에 실패하면 panic (`reqwest::blocking::Client::new` 와 동일)
     pub fn new(account_info: AccountConfig) -> Self {
-        Self { account_info }
+        let client = default_client_builder()
+            .build()
+            .expect("failed to build HTTP client");
+        Self::with_client(account_info, client)
     }
 
-    pub fn get_hashkey(&self, parameters: &[(&str, &str)]) -> KisResult<(HashMap<String, String>, String)> {
-        let url = "/uapi/hashkey";
-        let headers = [];
+    /// 호출자가 설정한 HTTP client 를 사용, 모든 요청이 이 client 의 connection pool 을 공유
+    ///
+    /// 설정 파일 옆에 캐시된 access token, websocket 접속키가 유효하면 재사용
+    pub fn with_client(account_info: AccountConfig, client: blocking::Client) -> Self {
+        let access_token = TokenKind::Access.load_cached(&account_info);
+        let approval_key = TokenKind::Approval.load_cached(&account_info);
+
+        let rate_limiter = Arc::new(RateLimiter::for_account(&account_info));
+
+        Self {
+            account_info,
+            client,
+            access_token: RwLock::new(access_token),
+            approval_key: RwLock::new(approval_key),
+            rate_limiter,
+            retry_policy: RetryPolicy::default(),
+        }
+    }
 
-        let req = self.make_request(url, RequestType::POST, &headers, parameters)?;
+    /// 조회 요청과 `order_stock_with_retry` 의 재시도 정책
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
 
-        let ret = req.parameters.clone();
-        let v = self.send_request(req);
-        if let Ok(val) = v {
-            return Ok((
-                ret,
-                val["HASH"]
-                    .to_string()
-                    .trim_matches('"')
-                    .to_string(),
-            ));
-        }
+    /// 다른 client 와 초당 호출 한도를 공유 (같은 appkey 를 쓰는 `KisApi`, `AsyncKisApi` 등)
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
 
-        Err("".into())
+    pub fn rate_limiter(&self) -> &Arc<RateLimiter> {
+        &self.rate_limiter
     }
 
-    pub fn issue_access_token(&mut self) -> KisResult<bool> {
-        if self.account_info.is_acces_token_valid() {
-            return Ok(true);
-        }
-        let url = "/oauth2/tokenP";
-        let headers = [];
-        let parameters = [];
-
-        let req = self.make_request(url, RequestType::POSTTOKEN, &headers, &parameters)?;
-
-        let v = self.send_request(req);
-        match v {
-            Ok(json_data) => {
-                self.account_info.set_access_token(
-                    json_data["access_token"]
-                        .as_str()
-                        .unwrap(),
-                );
-                Ok(true)
-            }
-            Err(_) => Ok(false),
-        }
+    pub fn account_info(&self) -> &AccountConfig {
+        &self.account_info
     }
 
-    fn make_request(
+    pub fn get_hashkey(
         &self,
-        url: &str,
-        req_type: RequestType,
-        headers: &[(&str, &str)],
         parameters: &[(&str, &str)],
-    ) -> KisResult<KisRequest> {
-        let mut req = KisRequest::new(req_type, &self.account_info);
-        let auth_header = format!("Bearer {}", self.account_info.get_access_token());
-        req.headers.insert(
-            header::AUTHORIZATION,
-            HeaderValue::from_str(auth_header.as_str())?,
-        );
+    ) -> KisResult<(HashMap<String, String>, String)> {
+        let call = client::hashkey(parameters);
+        let ret = call.ep.parameters.iter().cloned().collect();
+        Ok((ret, self.execute(call)?))
+    }
 
-        // request URL
-        req.url = req.url + url;
+    /// 보안인증키 발급, 유효한 token 이 있으면 재사용
+    ///
+    /// KIS 는 token 발급 횟수를 제한하므로 발급된 token 은 설정 파일 옆에 캐시한다.
+    pub fn issue_access_token(&self) -> KisResult<bool> {
+        self.issue(TokenKind::Access)?;
+        Ok(true)
+    }
+
+    pub fn is_access_token_valid(&self) -> bool {
+        self.access_token
+            .read()
+            .map(|t| t.is_valid())
+            .unwrap_or(false)
+    }
 
-        // additional headers
-        for (k, v) in headers {
-            req.headers
-                .insert(k.parse::<HeaderName>().unwrap(), v.parse().unwrap());
+    /// 접근토큰폐기, 종료 시 호출하며 캐시된 token 도 삭제
+    pub fn revoke_access_token(&self) -> KisResult<()> {
+        let mut access_token = self
+            .access_token
+            .write()
+            .map_err(|_| TokenKind::Access.poisoned())?;
+        if access_token.get_token().is_empty() {
+            return Ok(());
         }
 
-        // parameters : query or body
-        for (k, v) in parameters {
-            req.parameters
-                .insert(k.to_string(), v.to_string());
+        self.execute(client::revoke_access_token(access_token.get_token()))?;
+
+        *access_token = AccessToken::default();
+        if let Some(path) = TokenKind::Access.cache_path(&self.account_info) {
+            client::remove_cache(&path)?;
         }
 
-        Ok(req)
+        Ok(())
     }
 
-    fn make_request_hashkey(
-        &self,
-        url: &str,
-        req_type: RequestType,
-        headers: &[(&str, &str)],
-        body: HashMap<String, String>,
-    ) -> KisResult<KisRequest> {
-        let mut req = KisRequest::new(req_type, &self.account_info);
-        let auth_header = format!("Bearer {}", self.account_info.get_access_token());
-        req.headers.insert(
-            header::AUTHORIZATION,
-            HeaderValue::from_str(auth_header.as_str())?,
-        );
+    /// 실시간 (웹소켓) 접속키 발급, 유효한 접속키가 있으면 재사용
+    pub fn issue_approval_key(&self) -> KisResult<String> {
+        self.issue(TokenKind::Approval)
+    }
+
+    fn token(&self, kind: TokenKind) -> &RwLock<AccessToken> {
+        match kind {
+            TokenKind::Access => &self.access_token,
+            TokenKind::Approval => &self.approval_key,
+        }
+    }
+
+    /// 유효한 token 이 없으면 발급받아 캐시
+    fn issue(&self, kind: TokenKind) -> KisResult<String> {
+        let lock = self.token(kind);
+        if let Ok(token) = lock.read() {
+            if token.is_valid() {
+                return Ok(token.get_token().to_string());
+            }
+        }
+
+        let mut token = lock.write().map_err(|_| kind.poisoned())?;
+        // 다른 thread 에서 이미 재발급 했을 수 있음
+        if token.is_valid() {
+            return Ok(token.get_token().to_string());
+        }
+
+        let json_data = self.call(&kind.endpoint())?;
+
+        *token = kind.parse(&json_data)?;
+        if let Some(path) = kind.cache_path(&self.account_info) {
+            token.save(&path)?;
+        }
+
+        Ok(token.get_token().to_string())
+    }
 
-        // URL
-        req.url = req.url + url;
+    /// 요청을 보내고 응답을 결과 type 으로 변환
+    fn execute<T>(&self, call: Call<T>) -> KisResult<T> {
+        let v = if call.all_pages {
+            self.call_all_pages(&call.ep)?
+        } else {
+            self.call(&call.ep)?
+        };
+        call.parse(v)
+    }
+
+    /// 요청 전송, 응답 body 만 사용
+    fn call(&self, ep: &Endpoint) -> KisResult<serde_json::Value> {
+        Ok(self.call_response(ep)?.body)
+    }
+
+    /// 연속조회 page 를 모두 조회해 하나의 응답으로 합침
+    fn call_all_pages(&self, ep: &Endpoint) -> KisResult<serde_json::Value> {
+        let mut acc = serde_json::Value::Null;
+        for page in self.pages(ep.clone()) {
+            merge_page(&mut acc, page?);
+        }
+        Ok(acc)
+    }
 
-        // additional headers
-        for (k, v) in headers {
-            req.headers
-                .insert(k.parse::<HeaderName>().unwrap(), v.parse().unwrap());
+    fn pages(&self, ep: Endpoint) -> Pages<'_> {
+        Pages {
+            kis: self,
+            next: Some(ep),
         }
+    }
+
+    /// 조회(GET) 요청은 일시적인 실패를 `RetryPolicy` 에 따라 재시도
+    fn call_response(&self, ep: &Endpoint) -> KisResult<KisResponse> {
+        if ep.req_type != RequestType::GET {
+            return self.call_once(ep);
+        }
+
+        let mut retry = Retry::new(&self.retry_policy);
+        loop {
+            match self.call_once(ep) {
+                Ok(res) => return Ok(res),
+                Err(e) => thread::sleep(retry.on_error(e)?),
+            }
+        }
+    }
 
-        // parameters : query or body
-        req.parameters = body;
+    /// 주문은 hashkey 를 먼저 발급받고, OAuth 요청이 아니면 access token 추가
+    fn call_once(&self, ep: &Endpoint) -> KisResult<KisResponse> {
+        let hashkey = if ep.hashkey {
+            Some(self.execute(client::hashkey(&ep.parameters))?)
+        } else {
+            None
+        };
+        let access_token = if ep.is_oauth() {
+            None
+        } else {
+            Some(self.issue(TokenKind::Access)?)
+        };
 
-        Ok(req)
+        let req = ep.to_request(
+            &self.account_info,
+            access_token.as_deref(),
+            hashkey.as_deref(),
+        )?;
+        self.send_request(req)
     }
 
-    fn send_request(&self, req: KisRequest) -> KisResult<serde_json::Value> {
-        let client = reqwest::blocking::Client::new();
-        let res: blocking::Response;
+    fn send_request(&self, req: KisRequest) -> KisResult<KisResponse> {
+        // 주문은 시세 조회보다 먼저 token 을 받음
+        self.rate_limiter.acquire(Priority::from(req.req_type));
 
-        res = if let RequestType::GET = req.req_type {
+        let client = &self.client;
+
+        let res: blocking::Response = if let RequestType::GET = req.req_type {
             client
                 .get(req.url)
                 .headers(req.headers)
@@ -140,215 +248,233 @@ impl KisApi {
                 .send()?
         };
 
-        //TODO: Error handling
-        match res.status() {
-            reqwest::StatusCode::OK => {
-                // println!("Response Headers:\n{:#?}", res.headers());
-                let v: serde_json::Value = serde_json::from_str(&res.text()?)?;
-                Ok(v)
-            }
-            s => {
-                println!("Response Error : {} \n\t{:?}", s, res);
-                Err("Response Error".into())
-            }
-        }
+        let status = res.status();
+        let tr_cont = client::tr_cont(res.headers());
+        Ok(KisResponse {
+            body: parse_response(status, &res.text()?)?,
+            tr_cont,
+        })
     }
 
-    pub fn get_account_balance(&self) -> KisResult<serde_json::Value> {
-        let url = "/uapi/domestic-stock/v1/trading/inquire-balance";
-
-        let tr_id = if self.account_info.is_real() {
-            "TTTC8434R"
-        } else {
-            "VTTC8434R"
-        };
-        let headers = [("tr_id", tr_id)];
+    /// 주식잔고조회, 모든 page 의 보유 종목과 계좌 합계
+    pub fn get_account_balance(&self) -> KisResult<AccountBalance> {
+        self.execute(client::account_balance(&self.account_info))
+    }
 
-        let query = [
-            ("CANO", self.account_info.get_account_no()),
-            ("ACNT_PRDT_CD", "01"),
-            ("AFHR_FLPR_YN", "N"),
-            ("FNCG_AMT_AUTO_RDPT_YN", "N"),
-            ("FUND_STTL_ICLD_YN", "N"),
-            ("INQR_DVSN", "01"),
-            ("OFL_YN", "N"),
-            ("PRCS_DVSN", "01"),
-            ("UNPR_DVSN", "01"),
-            ("CTX_AREA_FK100", ""),
-            ("CTX_AREA_NK100", ""),
-        ];
+    /// 주식잔고조회 page 단위 조회
+    pub fn get_account_balance_pages(&self) -> Pages<'_> {
+        self.pages(client::account_balance(&self.account_info).ep)
+    }
 
-        let req = self.make_request(url, RequestType::GET, &headers, &query)?;
+    /// 매수가능조회 : `order_type`, `price` 로 주문할 때 주문가능현금과 최대 수량
+    ///
+    /// 시장가 계열 주문은 `price` 0 으로 조회하며 현재가 기준으로 계산된다.
+    pub fn get_buying_power(
+        &self,
+        ticker: &str,
+        order_type: OrderType,
+        price: u32,
+    ) -> KisResult<BuyingPower> {
+        self.execute(client::buying_power(
+            &self.account_info,
+            ticker,
+            order_type,
+            price,
+        )?)
+    }
 
-        self.send_request(req)
+    /// 신용매수가능조회 (실전투자 전용) : `credit_type` 으로 매수할 때 주문가능현금과 최대 수량
+    pub fn get_credit_buying_power(
+        &self,
+        ticker: &str,
+        order_type: OrderType,
+        price: u32,
+        credit_type: CreditType,
+    ) -> KisResult<BuyingPower> {
+        self.execute(client::credit_buying_power(
+            &self.account_info,
+            ticker,
+            order_type,
+            price,
+            credit_type,
+        )?)
     }
 
     pub fn order_buy_stock(
         &self,
         ticker: &str,
-        order_type: &str,
+        order_type: OrderType,
         count: u32,
         price: u32,
-    ) -> KisResult<serde_json::Value> {
-        self.order_stock(ticker, order_type, count, price, true)
+    ) -> KisResult<OrderAck> {
+        let order = client::simple_order(OrderSide::Buy, ticker, order_type, count, price)?;
+        self.order_stock(&order)
     }
 
     pub fn order_sell_stock(
         &self,
         ticker: &str,
-        order_type: &str,
+        order_type: OrderType,
         count: u32,
         price: u32,
-    ) -> KisResult<serde_json::Value> {
-        self.order_stock(ticker, order_type, count, price, false)
+    ) -> KisResult<OrderAck> {
+        let order = client::simple_order(OrderSide::Sell, ticker, order_type, count, price)?;
+        self.order_stock(&order)
+    }
+
+    pub fn order_stock(&self, order: &OrderRequest) -> KisResult<OrderAck> {
+        self.execute(client::order_stock(&self.account_info, order)?)
+    }
+
+    /// 주식주문(현금/신용), 일시적인 실패는 당일 주문 조회로 접수 여부를 확인한 뒤 재시도
+    ///
+    /// 주문 전에 같은 조건의 당일 주문을 조회해 두고, 실패 후 조회에 새 주문이 보이면
+    /// 다시 보내지 않고 그 주문을 돌려준다. 조회 전에는 `ORDER_SETTLE_DELAY` 이상 기다리고,
+    /// 재시도하지 않는 실패도 에러를 돌려주기 전에 한 번 조회한다. 업무 에러 (`Error::Api`) 는 바로 돌려준다.
+    /// 같은 조건이면 다른 곳에서 낸 주문도 같은 주문으로 보므로 (`retry::find_orders`)
+    /// 같은 종목을 동시에 주문하는 곳이 있으면 쓰지 않는다.
+    pub fn order_stock_with_retry(&self, order: &OrderRequest) -> KisResult<OrderAck> {
+        let call = client::order_stock(&self.account_info, order)?;
+        let history = OrderRetry::history(order);
+        let mut retry = OrderRetry::new(order, &self.retry_policy, &self.get_executions(&history)?);
+
+        loop {
+            let err = match self.call(&call.ep) {
+                Ok(v) => return call.parse(v),
+                Err(e) => e,
+            };
+            let pending = retry.on_failure(err)?;
+            thread::sleep(pending.wait);
+
+            // 포기하기 전에도 접수 여부 확인
+            if let Some(ack) = retry.resolve(self.get_executions(&history), pending)? {
+                return Ok(ack);
+            }
+        }
     }
 
-    pub fn order_stock(
+    /// 주식주문(정정), 정정 주문의 새 주문번호를 돌려줌
+    pub fn modify_order(
         &self,
-        ticker: &str,
-        order_type: &str,
-        count: u32,
+        order: &OrderAck,
+        order_type: OrderType,
+        quantity: OrderQuantity,
         price: u32,
-        buy: bool,
-    ) -> KisResult<serde_json::Value> {
-        let url = "/uapi/domestic-stock/v1/trading/order-cash";
+    ) -> KisResult<OrderAck> {
+        self.execute(client::modify_order(
+            &self.account_info,
+            order,
+            order_type,
+            quantity,
+            price,
+        )?)
+    }
 
-        let parameters = [
-            ("CANO", "50067252"),
-            ("ACNT_PRDT_CD", "01"),
-            ("PDNO", ticker),
-            ("ORD_DVSN", order_type),
-            ("ORD_QTY", &count.to_string()),
-            ("ORD_UNPR", &price.to_string()),
-            // ("CTAC_TLNO", ""),
-            // ("SLL_TYPE", "01"),
-            ("ALGO_NO", ""),
-        ];
+    /// 주식주문(취소), 취소 주문의 주문번호를 돌려줌
+    pub fn cancel_order(&self, order: &OrderAck, quantity: OrderQuantity) -> KisResult<OrderAck> {
+        self.execute(client::cancel_order(&self.account_info, order, quantity)?)
+    }
 
-        let hash_data = self.get_hashkey(&parameters)?;
+    /// 주식예약주문 (실전투자 전용), 다음 영업일 장 시작 전에 전송
+    pub fn order_reservation(&self, order: &ReservationOrder) -> KisResult<ReservationAck> {
+        self.execute(client::order_reservation(&self.account_info, order)?)
+    }
 
-        let tr_id = if buy {
-            if self.account_info.is_real() {
-                "TTTC0802U"
-            } else {
-                "VTTC0802U"
-            }
-        } else {
-            if self.account_info.is_real() {
-                "TTTC0801U"
-            } else {
-                "VTTC0801U"
-            }
-        };
-        let headers = [
-            ("custtype", "P"),
-            ("tr_id", tr_id),
-            ("hashkey", hash_data.1.trim_matches('"')),
-        ];
+    /// 주식예약주문정정 (실전투자 전용), 예약 내용을 `order` 로 변경
+    pub fn modify_reservation(
+        &self,
+        reservation: &Reservation,
+        order: &ReservationOrder,
+    ) -> KisResult<()> {
+        self.execute(client::modify_reservation(
+            &self.account_info,
+            reservation,
+            order,
+        )?)
+    }
 
-        let req = self.make_request_hashkey(url, RequestType::POST, &headers, hash_data.0)?;
-        self.send_request(req)
+    /// 주식예약주문취소 (실전투자 전용)
+    pub fn cancel_reservation(&self, reservation: &Reservation) -> KisResult<()> {
+        self.execute(client::cancel_reservation(&self.account_info, reservation)?)
     }
 
-    pub fn get_ordered_list(&self) -> KisResult<serde_json::Value> {
-        if !self.account_info.is_real() {
-            return Err("Not Available for virtual account".into());
-        }
-        let url = "/uapi/domestic-stock/v1/trading/inquire-psbl-rvsecncl";
+    /// 주식예약주문조회 (실전투자 전용), 예약주문일자 `start_date` ~ `end_date` (YYYYMMDD)
+    pub fn get_reservations(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> KisResult<Vec<Reservation>> {
+        self.execute(client::reservations(
+            &self.account_info,
+            start_date,
+            end_date,
+        )?)
+    }
 
-        let tr_id = "TTTC8036R";
-        let headers = [("tr_id", tr_id)];
+    /// 주식일별주문체결조회, 모든 page 의 주문별 체결 현황
+    pub fn get_executions(&self, query: &ExecutionQuery) -> KisResult<Vec<Execution>> {
+        self.execute(client::executions(&self.account_info, query)?)
+    }
 
-        let query = [
-            ("CANO", self.account_info.get_account_no()),
-            ("ACNT_PRDT_CD", "01"),
-            ("CTX_AREA_FK100", ""),
-            ("CTX_AREA_NK100", ""),
-            ("INQR_DVSN_1", "0"),
-            ("INQR_DVSN_2", "0"),
-        ];
+    /// 주식일별주문체결조회 page 단위 조회
+    pub fn get_executions_pages(&self, query: &ExecutionQuery) -> KisResult<Pages<'_>> {
+        Ok(self.pages(client::executions(&self.account_info, query)?.ep))
+    }
 
-        let req = self.make_request(url, RequestType::GET, &headers, &query)?;
+    /// 주식정정취소가능주문조회, 모든 page 의 output 을 합친 응답
+    pub fn get_ordered_list(&self) -> KisResult<serde_json::Value> {
+        self.execute(client::ordered_list(&self.account_info)?)
+    }
 
-        self.send_request(req)
+    /// 주식정정취소가능주문조회 page 단위 조회
+    pub fn get_ordered_list_pages(&self) -> KisResult<Pages<'_>> {
+        Ok(self.pages(client::ordered_list(&self.account_info)?.ep))
     }
 
-    // 국내주식시세
-    /// 주식현재가 시세[v1_국내주식-008]
-    pub fn get_stock_current_price(&self, ticker: &str) -> KisResult<serde_json::Value> {
-        let url = "/uapi/domestic-stock/v1/quotations/inquire-price";
-        let headers = [("tr_id", "FHKST01010100")];
-        let query = [("fid_cond_mrkt_div_code", "J"), ("fid_input_iscd", ticker)];
+    /// 기간별매매손익현황조회 (실전투자 전용), 매매일자별 종목 실현손익과 기간 합계
+    pub fn get_trade_profits(&self, query: &ProfitQuery) -> KisResult<ProfitReport<TradeProfit>> {
+        self.execute(client::trade_profits(&self.account_info, query)?)
+    }
 
-        let req = self.make_request(url, RequestType::GET, &headers, &query)?;
+    /// 기간별손익일별합산조회 (실전투자 전용), 일자별 실현손익과 기간 합계
+    pub fn get_daily_profits(&self, query: &ProfitQuery) -> KisResult<ProfitReport<DailyProfit>> {
+        self.execute(client::daily_profits(&self.account_info, query)?)
+    }
 
-        self.send_request(req)
+    // 국내주식시세
+    /// 주식현재가 시세[v1_국내주식-008]
+    pub fn get_stock_current_price(&self, ticker: &str) -> KisResult<CurrentPrice> {
+        self.execute(client::stock_current_price(ticker))
     }
 
     /// 주식현재가 체결[v1_국내주식-009]
-    pub fn get_stock_current_concluded(&self, ticker: &str) -> KisResult<serde_json::Value> {
-        let url = "/uapi/domestic-stock/v1/quotations/inquire-ccnl";
-        let headers = [("tr_id", "FHKST01010300")];
-        let query = [("fid_cond_mrkt_div_code", "J"), ("fid_input_iscd", ticker)];
-        let req = self.make_request(url, RequestType::GET, &headers, &query)?;
-
-        self.send_request(req)
+    pub fn get_stock_current_concluded(&self, ticker: &str) -> KisResult<Vec<Conclusion>> {
+        self.execute(client::stock_current_concluded(ticker))
     }
 
     /// 주식현재가 일자별[v1_국내주식-010]
-    pub fn get_stock_daily_price(&self, ticker: &str) -> KisResult<serde_json::Value> {
-        let url = "/uapi/domestic-stock/v1/quotations/inquire-daily-price";
-        let headers = [("tr_id", "FHKST01010400")];
-        let query = [
-            ("fid_cond_mrkt_div_code", "J"),
-            ("fid_input_iscd", ticker),
-            ("fid_org_adj_prc", "1"),
-            ("fid_period_div_code", "D"), // D,W,M
-        ];
-
-        let req = self.make_request(url, RequestType::GET, &headers, &query)?;
-
-        self.send_request(req)
+    pub fn get_stock_daily_price(&self, ticker: &str) -> KisResult<Vec<DailyPrice>> {
+        self.execute(client::stock_daily_price(ticker))
     }
 
     /// 주식현재가 호가 예상체결[v1_국내주식-011]
-    pub fn get_stock_bid_ask_prices(&self, ticker: &str) -> KisResult<serde_json::Value> {
-        let url = "/uapi/domestic-stock/v1/quotations/inquire-asking-price-exp-ccn";
-        let headers = [("tr_id", "FHKST01010200")];
-        let query = [("fid_cond_mrkt_div_code", "J"), ("fid_input_iscd", ticker)];
-        let req = self.make_request(url, RequestType::GET, &headers, &query)?;
-
-        self.send_request(req)
+    pub fn get_stock_bid_ask_prices(&self, ticker: &str) -> KisResult<BidAskPrices> {
+        self.execute(client::stock_bid_ask_prices(ticker))
     }
 
     /// 주식현재가 투자자[v1_국내주식-012]
-    pub fn get_stock_investor_list(&self, ticker: &str) -> KisResult<serde_json::Value> {
-        let url = "/uapi/domestic-stock/v1/quotations/inquire-investor";
-        let headers = [("tr_id", "FHKST01010900")];
-        let query = [("fid_cond_mrkt_div_code", "J"), ("fid_input_iscd", ticker)];
-        let req = self.make_request(url, RequestType::GET, &headers, &query)?;
-
-        self.send_request(req)
+    pub fn get_stock_investor_list(&self, ticker: &str) -> KisResult<Vec<InvestorTrading>> {
+        self.execute(client::stock_investor_list(ticker))
     }
 
     /// 주식현재가 회원사[v1_국내주식-013]
-    pub fn get_stock_membership_list(&self, ticker: &str) -> KisResult<serde_json::Value> {
-        let url = "/uapi/domestic-stock/v1/quotations/inquire-member";
-        let headers = [("tr_id", "FHKST01010600")];
-        let query = [("fid_cond_mrkt_div_code", "J"), ("fid_input_iscd", ticker)];
-        let req = self.make_request(url, RequestType::GET, &headers, &query)?;
-
-        self.send_request(req)
+    pub fn get_stock_membership_list(&self, ticker: &str) -> KisResult<MemberTrading> {
+        self.execute(client::stock_membership_list(ticker))
     }
 
     /// ELW현재가 시세[v1_국내주식-014] not tested
     pub fn get_stock_elw_price(&self, ticker: &str) -> KisResult<serde_json::Value> {
-        let url = "/uapi/domestic-stock/v1/quotations/inquire-elw-price";
-        let headers = [("tr_id", "FHKEW15010000")];
-        let query = [("fid_cond_mrkt_div_code", "W"), ("fid_input_iscd", ticker)];
-        let req = self.make_request(url, RequestType::GET, &headers, &query)?;
-
-        self.send_request(req)
+        self.execute(client::stock_elw_price(ticker))
     }
 
     /// 국내주식기간별시세(일/주/월/년)[v1_국내주식-016] R not tested
@@ -359,19 +485,7 @@ impl KisApi {
         end: &str,
         duration: &str,
     ) -> KisResult<serde_json::Value> {
-        let url = "/uapi/domestic-stock/v1/quotations/inquire-daily-itemchartprice";
-        let headers = [("tr_id", "[실전투자]")];
-        let query = [
-            ("fid_cond_mrkt_div_code", "J"),
-            ("fid_input_iscd", ticker),
-            ("fid_input_date_1", begin),
-            ("fid_input_date_2", end),
-            ("fid_period_div_code", duration),
-            ("FID_ORG_ADJ_PRC", "0"), // 0:수정주가 1:원주가
-        ];
-        let req = self.make_request(url, RequestType::GET, &headers, &query)?;
-
-        self.send_request(req)
+        self.execute(client::stock_duration_prices(ticker, begin, end, duration))
     }
 
     /// 국내주식업종기간별시세(일/주/월/년)[v1_국내주식-021] R not tested
@@ -382,40 +496,523 @@ impl KisApi {
         end: &str,
         duration: &str,
     ) -> KisResult<serde_json::Value> {
-        let url = "/uapi/domestic-stock/v1/quotations/inquire-daily-indexchartprice";
-        let headers = [("tr_id", "[실전투자]")];
-        let query = [
-            ("fid_cond_mrkt_div_code", "U"),
-            ("fid_input_iscd", section),
-            ("fid_input_date_1", begin),
-            ("fid_input_date_2", end),
-            ("fid_period_div_code", duration),
-        ];
-        let req = self.make_request(url, RequestType::GET, &headers, &query)?;
+        self.execute(client::sector_duration_prices(
+            section, begin, end, duration,
+        ))
+    }
+}
 
-        self.send_request(req)
+/// 연속조회 iterator, 응답의 tr_cont 를 따라 다음 page 를 조회
+///
+/// 에러가 나면 그 에러를 돌려주고 끝난다.
+pub struct Pages<'a> {
+    kis: &'a KisApi,
+    next: Option<Endpoint>,
+}
+
+impl Iterator for Pages<'_> {
+    type Item = KisResult<serde_json::Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ep = self.next.take()?;
+        match self.kis.call_response(&ep) {
+            Ok(res) => {
+                self.next = ep.next_page(&res);
+                Some(Ok(res.body))
+            }
+            Err(e) => Some(Err(e)),
+        }
     }
 }
 
+/// KisApi 기본 HTTP client 설정 : keep-alive, connection pool, timeout, user agent
+pub fn default_client_builder() -> blocking::ClientBuilder {
+    configure_client!(blocking::Client::builder())
+}
+
 #[cfg(test)]
 mod unit_test {
     use super::*;
-    use crate::kis::load_account_config;
+    use crate::kis::mock_server::{MockRequest, MockResponse, MockServer};
+    use crate::kis::{load_account_config, Error, FillFilter, OrderSide};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::{Duration, Instant};
 
-    static TICKER: &'static str = "003490";
+    static TICKER: &str = "003490";
 
     fn setup() -> KisApi {
-        let mut kis = KisApi::new(load_account_config("./secret", false).unwrap());
+        let kis = KisApi::new(load_account_config("./secret", false).unwrap());
         let res = kis.issue_access_token();
-        assert_eq!(res.unwrap(), true);
+        assert!(res.unwrap());
         kis
     }
 
+    /// 호출 한도 없이 mock server 에 접속
+    fn mock_kis(server: &MockServer) -> KisApi {
+        KisApi::new(AccountConfig::mock(server.url(), false))
+            .with_rate_limiter(Arc::new(RateLimiter::new(u32::MAX)))
+    }
+
+    const MOCK_PRICE: &str = r#"{"rt_cd":"0","msg_cd":"MCA00000","msg1":"정상처리 되었습니다.","output":{"stck_prpr":"70000","prdy_vrss":"0","prdy_ctrt":"0.00","acml_vol":"0","acml_tr_pbmn":"0","stck_oprc":"70000","stck_hgpr":"70000","stck_lwpr":"70000","stck_mxpr":"91000","stck_llam":"49000","stck_sdpr":"70000"}}"#;
+
+    #[test]
+    fn test_pooled_client_reuses_connection() {
+        const COUNT: usize = 10;
+
+        let server = MockServer::start(|_| MockResponse::ok(MOCK_PRICE));
+        let kis = mock_kis(&server);
+        kis.issue_access_token().unwrap();
+        for _ in 0..COUNT {
+            kis.get_stock_current_price(TICKER).unwrap();
+        }
+        // token 발급부터 모든 요청이 하나의 연결을 재사용
+        assert_eq!(server.connections(), 1);
+
+        let url = format!(
+            "{}/uapi/domestic-stock/v1/quotations/inquire-price",
+            server.url()
+        );
+        for _ in 0..COUNT {
+            blocking::Client::new().get(&url).send().unwrap();
+        }
+        // 요청마다 새 client 를 만들면 매번 새 연결
+        assert_eq!(server.connections(), 1 + COUNT);
+    }
+
+    /// 요청마다 새 client 를 만들 때와 pool 된 client 를 재사용할 때의 latency 비교
+    /// `cargo test -- --ignored bench_pooled_client` 로 실행
+    #[test]
+    #[ignore]
+    fn bench_pooled_client_against_mock_server() {
+        const COUNT: u32 = 200;
+
+        let server = MockServer::start(|_| MockResponse::ok(MOCK_PRICE));
+        let kis = mock_kis(&server);
+        kis.issue_access_token().unwrap();
+
+        let start = Instant::now();
+        for _ in 0..COUNT {
+            kis.get_stock_current_price(TICKER).unwrap();
+        }
+        let pooled = start.elapsed();
+
+        let url = format!(
+            "{}/uapi/domestic-stock/v1/quotations/inquire-price",
+            server.url()
+        );
+        let start = Instant::now();
+        for _ in 0..COUNT {
+            let res = blocking::Client::new().get(&url).send().unwrap();
+            assert!(res.status().is_success());
+        }
+        let fresh = start.elapsed();
+
+        println!(
+            "pooled client : {:?}/req, new client per request : {:?}/req",
+            pooled / COUNT,
+            fresh / COUNT
+        );
+        assert!(pooled < fresh);
+    }
+
+    #[test]
+    fn test_with_client_user_agent() {
+        let server = MockServer::start(|req| {
+            let ua = req.header("user-agent").unwrap_or_default();
+            assert_eq!(ua, "custom-agent");
+            MockResponse::ok(MOCK_PRICE)
+        });
+        let client = default_client_builder()
+            .user_agent("custom-agent")
+            .build()
+            .unwrap();
+        let kis = KisApi::with_client(AccountConfig::mock(server.url(), false), client);
+
+        assert!(kis.get_stock_current_price(TICKER).is_ok());
+        assert_eq!(server.requests(), 1);
+    }
+
+    const MOCK_RATE_LIMIT: &str =
+        r#"{"rt_cd":"1","msg_cd":"EGW00201","msg1":"초당 거래건수를 초과하였습니다."}"#;
+    const MOCK_ORDER: &str = r#"{"rt_cd":"0","msg_cd":"APBK0013","msg1":"주문 전송 완료 되었습니다.","output":{"KRX_FWDG_ORD_ORGNO":"91252","ODNO":"0000117057","ORD_TMD":"121052"}}"#;
+
+    fn mock_retry_policy() -> RetryPolicy {
+        RetryPolicy::new(3, Duration::from_millis(1), Duration::from_millis(10))
+    }
+
+    fn mock_order() -> OrderRequest {
+        OrderRequest::buy("005930")
+            .quantity(1)
+            .price(70000)
+            .build()
+            .unwrap()
+    }
+
+    /// 당일 주문 조회 응답, `orders` 는 주문번호 목록
+    fn mock_order_history(orders: &[&str]) -> String {
+        let rows: Vec<_> = orders
+            .iter()
+            .map(|odno| {
+                serde_json::json!({"ord_dt": "20240105", "pdno": "005930", "sll_buy_dvsn_cd": "02", "ord_dvsn_cd": "00", "ord_qty": "1", "ord_unpr": "70000", "orgn_odno": "", "ord_gno_brno": "91252", "odno": odno, "ord_tmd": "121052", "tot_ccld_qty": "0"})
+            })
+            .collect();
+        serde_json::json!({"rt_cd": "0", "msg_cd": "", "msg1": "", "output1": rows, "output2": {}})
+            .to_string()
+    }
+
+    #[test]
+    fn test_retry_quotation() {
+        let count = AtomicUsize::new(0);
+        let server = MockServer::start(move |_| match count.fetch_add(1, Ordering::SeqCst) {
+            0 => MockResponse::status(500, MOCK_RATE_LIMIT),
+            1 => MockResponse::status(502, "Bad Gateway"),
+            _ => MockResponse::ok(MOCK_PRICE),
+        });
+        let kis = mock_kis(&server).with_retry_policy(mock_retry_policy());
+
+        assert!(kis.get_stock_current_price(TICKER).is_ok());
+        assert_eq!(server.requests(), 3);
+
+        // 재시도 횟수를 넘으면 마지막 에러
+        let server = MockServer::start(|_| MockResponse::status(500, MOCK_RATE_LIMIT));
+        let kis = mock_kis(&server).with_retry_policy(mock_retry_policy());
+        assert!(matches!(
+            kis.get_stock_current_price(TICKER),
+            Err(Error::Api { msg_cd, .. }) if msg_cd == "EGW00201"
+        ));
+        assert_eq!(server.requests(), 4);
+    }
+
+    #[test]
+    fn test_order_not_retried() {
+        let server = MockServer::start(|req| match req.path.as_str() {
+            "/uapi/hashkey" => MockResponse::ok(r#"{"HASH":"mock-hash"}"#),
+            _ => MockResponse::status(500, MOCK_RATE_LIMIT),
+        });
+        let kis = mock_kis(&server).with_retry_policy(mock_retry_policy());
+
+        assert!(kis.order_stock(&mock_order()).is_err());
+        // hashkey + 주문 1회
+        assert_eq!(server.requests(), 2);
+    }
+
+    #[test]
+    fn test_order_with_retry_deduplicated() {
+        // 첫 주문은 접수되었지만 응답이 5xx
+        let orders = AtomicUsize::new(0);
+        let server = MockServer::start(move |req| match req.path.as_str() {
+            "/uapi/hashkey" => MockResponse::ok(r#"{"HASH":"mock-hash"}"#),
+            "/uapi/domestic-stock/v1/trading/inquire-daily-ccld" => {
+                match orders.load(Ordering::SeqCst) {
+                    0 => MockResponse::ok(&mock_order_history(&["0000000001"])),
+                    _ => MockResponse::ok(&mock_order_history(&["0000000001", "0000117057"])),
+                }
+            }
+            _ => {
+                orders.fetch_add(1, Ordering::SeqCst);
+                MockResponse::status(502, "Bad Gateway")
+            }
+        });
+        let kis = mock_kis(&server).with_retry_policy(mock_retry_policy());
+
+        let ack = kis.order_stock_with_retry(&mock_order()).unwrap();
+        assert_eq!(ack.order_no, "0000117057");
+        // 조회, hashkey, 주문, 조회
+        assert_eq!(server.requests(), 4);
+    }
+
+    #[test]
+    fn test_order_with_retry_resend() {
+        // 첫 주문이 접수되지 않았으면 다시 보냄
+        let orders = AtomicUsize::new(0);
+        let server = MockServer::start(move |req| match req.path.as_str() {
+            "/uapi/hashkey" => MockResponse::ok(r#"{"HASH":"mock-hash"}"#),
+            "/uapi/domestic-stock/v1/trading/inquire-daily-ccld" => {
+                MockResponse::ok(&mock_order_history(&[]))
+            }
+            _ => match orders.fetch_add(1, Ordering::SeqCst) {
+                0 => MockResponse::status(500, MOCK_RATE_LIMIT),
+                _ => MockResponse::ok(MOCK_ORDER),
+            },
+        });
+        let kis = mock_kis(&server).with_retry_policy(mock_retry_policy());
+
+        let ack = kis.order_stock_with_retry(&mock_order()).unwrap();
+        assert_eq!(ack.order_no, "0000117057");
+        // 조회, hashkey, 주문, 조회, hashkey, 주문
+        assert_eq!(server.requests(), 6);
+    }
+
+    #[test]
+    fn test_order_with_retry_exhausted() {
+        // 재시도 없이 포기하더라도 접수된 주문은 돌려줌
+        let orders = AtomicUsize::new(0);
+        let server = MockServer::start(move |req| match req.path.as_str() {
+            "/uapi/hashkey" => MockResponse::ok(r#"{"HASH":"mock-hash"}"#),
+            "/uapi/domestic-stock/v1/trading/inquire-daily-ccld" => {
+                match orders.load(Ordering::SeqCst) {
+                    0 => MockResponse::ok(&mock_order_history(&[])),
+                    _ => MockResponse::ok(&mock_order_history(&["0000117057"])),
+                }
+            }
+            _ => {
+                orders.fetch_add(1, Ordering::SeqCst);
+                MockResponse::status(502, "Bad Gateway")
+            }
+        });
+        let kis = mock_kis(&server).with_retry_policy(RetryPolicy::none());
+
+        let ack = kis.order_stock_with_retry(&mock_order()).unwrap();
+        assert_eq!(ack.order_no, "0000117057");
+        // 조회, hashkey, 주문, 조회
+        assert_eq!(server.requests(), 4);
+
+        // 접수되지 않았으면 주문 에러
+        let server = MockServer::start(|req| match req.path.as_str() {
+            "/uapi/hashkey" => MockResponse::ok(r#"{"HASH":"mock-hash"}"#),
+            "/uapi/domestic-stock/v1/trading/inquire-daily-ccld" => {
+                MockResponse::ok(&mock_order_history(&[]))
+            }
+            _ => MockResponse::status(502, "Bad Gateway"),
+        });
+        let kis = mock_kis(&server).with_retry_policy(RetryPolicy::none());
+        assert!(matches!(
+            kis.order_stock_with_retry(&mock_order()),
+            Err(Error::Http { .. })
+        ));
+    }
+
+    #[test]
+    fn test_order_with_retry_rejected() {
+        // 업무 에러는 조회와 재시도 없이 바로 에러
+        let server = MockServer::start(|req| match req.path.as_str() {
+            "/uapi/hashkey" => MockResponse::ok(r#"{"HASH":"mock-hash"}"#),
+            "/uapi/domestic-stock/v1/trading/inquire-daily-ccld" => {
+                MockResponse::ok(&mock_order_history(&[]))
+            }
+            _ => MockResponse::ok(
+                r#"{"rt_cd":"1","msg_cd":"APBK0919","msg1":"주문가능금액을 초과 했습니다"}"#,
+            ),
+        });
+        let kis = mock_kis(&server).with_retry_policy(mock_retry_policy());
+
+        assert!(matches!(
+            kis.order_stock_with_retry(&mock_order()),
+            Err(Error::Api { msg_cd, .. }) if msg_cd == "APBK0919"
+        ));
+        // 조회, hashkey, 주문
+        assert_eq!(server.requests(), 3);
+    }
+
+    /// 잔고 3 page : 요청의 연속조회키로 page 를 구분
+    fn mock_balance_pages(req: &MockRequest) -> MockResponse {
+        let page = match req.query.contains("CTX_AREA_NK100=P") {
+            false => 1,
+            true if req.query.contains("CTX_AREA_NK100=P2") => 2,
+            true => 3,
+        };
+        if page > 1 {
+            assert_eq!(req.header("tr_cont"), Some("N"));
+        }
+        let body = serde_json::json!({
+            "rt_cd": "0", "msg_cd": "", "msg1": "",
+            "ctx_area_fk100": "F", "ctx_area_nk100": format!("P{}", page + 1),
+            "output1": [{"pdno": format!("00000{page}"), "hldg_qty": "1", "ord_psbl_qty": "1", "pchs_avg_pric": "1000.0", "prpr": "1000", "evlu_pfls_amt": "0", "evlu_pfls_rt": "0.00"}],
+            "output2": [{"dnca_tot_amt": page.to_string(), "prvs_rcdl_excc_amt": "0", "tot_evlu_amt": "0", "nass_amt": "0"}]
+        });
+        let tr_cont = if page < 3 { "M" } else { "D" };
+        MockResponse::ok(&body.to_string()).header("tr_cont", tr_cont)
+    }
+
+    #[test]
+    fn test_account_balance_all_pages() {
+        let server = MockServer::start(mock_balance_pages);
+        let kis = mock_kis(&server);
+
+        let balance = kis.get_account_balance().unwrap();
+        let tickers: Vec<_> = balance.holdings.iter().map(|h| h.ticker.as_str()).collect();
+        assert_eq!(tickers, ["000001", "000002", "000003"]);
+        assert_eq!(balance.summary.deposit, 3);
+        assert_eq!(server.requests(), 3);
+
+        let pages: Vec<_> = kis
+            .get_account_balance_pages()
+            .collect::<KisResult<_>>()
+            .unwrap();
+        assert_eq!(pages.len(), 3);
+    }
+
+    #[test]
+    fn test_buying_power_request() {
+        let server = MockServer::start(|req| {
+            assert_eq!(
+                req.path,
+                "/uapi/domestic-stock/v1/trading/inquire-psbl-order"
+            );
+            assert_eq!(req.header("tr_id"), Some("VTTC8908R"));
+            assert!(req.query.contains("PDNO=005930"));
+            assert!(req.query.contains("ORD_DVSN=01"));
+            assert!(req.query.contains("ORD_UNPR=0"));
+            MockResponse::ok(
+                r#"{"rt_cd":"0","msg_cd":"","msg1":"","output":{"ord_psbl_cash":"1000000","psbl_qty_calc_unpr":"70000","nrcvb_buy_amt":"980000","nrcvb_buy_qty":"14","max_buy_amt":"3200000","max_buy_qty":"45"}}"#,
+            )
+        });
+        let kis = mock_kis(&server);
+
+        let power = kis
+            .get_buying_power("005930", OrderType::Market, 0)
+            .unwrap();
+        assert_eq!(power.cash_quantity, 14);
+        assert!(matches!(
+            kis.get_buying_power("005930", OrderType::Limit, 0),
+            Err(Error::InvalidRequest(_))
+        ));
+        assert_eq!(server.requests(), 1);
+    }
+
+    #[test]
+    fn test_executions_request() {
+        let server = MockServer::start(|req| {
+            assert_eq!(
+                req.path,
+                "/uapi/domestic-stock/v1/trading/inquire-daily-ccld"
+            );
+            // 3개월 이전 기간
+            assert_eq!(req.header("tr_id"), Some("VTSC9115R"));
+            assert!(req.query.contains("INQR_STRT_DT=20240102"));
+            assert!(req.query.contains("INQR_END_DT=20240105"));
+            assert!(req.query.contains("SLL_BUY_DVSN_CD=02"));
+            assert!(req.query.contains("PDNO=005930"));
+            assert!(req.query.contains("CCLD_DVSN=01"));
+            let first = !req.query.contains("CTX_AREA_NK100=P2");
+            let odno = if first { "0000117057" } else { "0000117058" };
+            let body = serde_json::json!({
+                "rt_cd": "0", "msg_cd": "", "msg1": "",
+                "ctx_area_fk100": "F", "ctx_area_nk100": "P2",
+                "output1": [{"ord_dt": "20240105", "pdno": "005930", "sll_buy_dvsn_cd": "02", "ord_dvsn_cd": "00", "ord_qty": "10", "ord_unpr": "70000", "ord_gno_brno": "91252", "odno": odno, "tot_ccld_qty": "10", "avg_prvs": "70000", "tot_ccld_amt": "700000", "rmn_qty": "0"}],
+                "output2": {"tot_ord_qty": "20", "tot_ccld_qty": "20"}
+            });
+            MockResponse::ok(&body.to_string()).header("tr_cont", if first { "M" } else { "D" })
+        });
+        let kis = mock_kis(&server);
+        let query = ExecutionQuery::new("20240102", "20240105")
+            .side(OrderSide::Buy)
+            .ticker("005930")
+            .fill(FillFilter::Filled);
+
+        let executions = kis.get_executions(&query).unwrap();
+        let orders: Vec<_> = executions.iter().map(|e| e.order_no.as_str()).collect();
+        assert_eq!(orders, ["0000117057", "0000117058"]);
+        assert!(executions.iter().all(Execution::is_filled));
+        assert_eq!(server.requests(), 2);
+
+        assert!(matches!(
+            kis.get_executions(&ExecutionQuery::new("20240105", "20240102")),
+            Err(Error::InvalidRequest(_))
+        ));
+        assert_eq!(server.requests(), 2);
+    }
+
+    #[test]
+    fn test_profit_request() {
+        let server = MockServer::start(|req| {
+            let tr_id = req.header("tr_id").unwrap();
+            assert!(req.query.contains("INQR_STRT_DT=20240102"));
+            assert!(req.query.contains("INQR_END_DT=20240105"));
+            let body = match req.path.as_str() {
+                "/uapi/domestic-stock/v1/trading/inquire-period-trade-profit" => {
+                    assert_eq!(tr_id, "TTTC8715R");
+                    r#"{"rt_cd":"0","msg_cd":"","msg1":"","output1":[{"trad_dt":"20240105","pdno":"005930","buy_amt":"685000","sll_amt":"700000","fee":"210","tl_tax":"1800","rlzt_pfls":"12990"}],"output2":{"tot_rlzt_pfls":"12990"}}"#
+                }
+                "/uapi/domestic-stock/v1/trading/inquire-period-profit" => {
+                    assert_eq!(tr_id, "TTTC8708R");
+                    r#"{"rt_cd":"0","msg_cd":"","msg1":"","output1":[{"trad_dt":"20240105","sll_amt":"700000","rlzt_pfls":"12990"}],"output2":{"tot_rlzt_pfls":"12990"}}"#
+                }
+                path => panic!("unexpected path {path}"),
+            };
+            MockResponse::ok(body)
+        });
+        let kis = KisApi::new(AccountConfig::mock(server.url(), true))
+            .with_rate_limiter(Arc::new(RateLimiter::new(u32::MAX)));
+        let query = ProfitQuery::new("20240102", "20240105");
+
+        let trades = kis.get_trade_profits(&query).unwrap();
+        assert_eq!(
+            trades.records[0].realized_profit,
+            trades.summary.realized_profit
+        );
+        let days = kis.get_daily_profits(&query).unwrap();
+        assert_eq!(days.records[0].trade_date, "20240105");
+        assert_eq!(server.requests(), 2);
+
+        // 모의투자 미지원
+        assert!(matches!(
+            mock_kis(&server).get_trade_profits(&query),
+            Err(Error::Config(_))
+        ));
+        assert_eq!(server.requests(), 2);
+    }
+
+    #[test]
+    fn test_reservation_request() {
+        let server = MockServer::start(|req| {
+            let body: serde_json::Value = serde_json::from_str(&req.body).unwrap_or_default();
+            match req.path.as_str() {
+                "/uapi/hashkey" => MockResponse::ok(r#"{"HASH":"mock-hash"}"#),
+                "/uapi/domestic-stock/v1/trading/order-resv" => {
+                    assert_eq!(req.method, "POST");
+                    assert_eq!(req.header("tr_id"), Some("CTSC0008U"));
+                    assert_eq!(req.header("custtype"), Some("P"));
+                    assert_eq!(req.header("hashkey"), Some("mock-hash"));
+                    assert_eq!(body["SLL_BUY_DVSN_CD"], "02");
+                    assert_eq!(body["ORD_DVSN_CD"], "00");
+                    assert_eq!(body["RSVN_ORD_END_DT"], "20240131");
+                    MockResponse::ok(
+                        r#"{"rt_cd":"0","msg_cd":"","msg1":"","output":{"RSVN_ORD_SEQ":"27"}}"#,
+                    )
+                }
+                "/uapi/domestic-stock/v1/trading/order-resv-ccnl" => {
+                    assert_eq!(req.method, "GET");
+                    assert_eq!(req.header("tr_id"), Some("CTSC0004R"));
+                    assert!(req.query.contains("RSVN_ORD_ORD_DT=20240105"));
+                    MockResponse::ok(
+                        r#"{"rt_cd":"0","msg_cd":"","msg1":"","ctx_area_fk200":"","ctx_area_nk200":"","output":[{"rsvn_ord_seq":"27","rsvn_ord_ord_dt":"20240105","pdno":"005930","sll_buy_dvsn_cd":"02","ord_dvsn_cd":"00","ord_rsvn_qty":"10","ord_rsvn_unpr":"70000","rsvn_end_dt":"20240131","cncl_ord_dt":""}]}"#,
+                    )
+                }
+                "/uapi/domestic-stock/v1/trading/order-resv-rvsecncl" => {
+                    assert_eq!(req.header("tr_id"), Some("CTSC0009U"));
+                    assert_eq!(body["RSVN_ORD_SEQ"], "27");
+                    assert_eq!(body["RSVN_ORD_ORD_DT"], "20240105");
+                    MockResponse::ok(
+                        r#"{"rt_cd":"0","msg_cd":"","msg1":"","output":{"NRML_PRCS_YN":"Y"}}"#,
+                    )
+                }
+                path => panic!("unexpected path {path}"),
+            }
+        });
+        let kis = KisApi::new(AccountConfig::mock(server.url(), true))
+            .with_rate_limiter(Arc::new(RateLimiter::new(u32::MAX)));
+        let order = ReservationOrder::new(mock_order()).end_date("20240131");
+
+        assert_eq!(kis.order_reservation(&order).unwrap().seq, 27);
+        let reservations = kis.get_reservations("20240105", "20240105").unwrap();
+        assert_eq!(reservations[0].seq, 27);
+        assert!(!reservations[0].is_cancelled());
+        assert!(kis.cancel_reservation(&reservations[0]).is_ok());
+        // hashkey + 예약, 조회, hashkey + 취소
+        assert_eq!(server.requests(), 5);
+
+        assert!(matches!(
+            mock_kis(&server).order_reservation(&order),
+            Err(Error::Config(_))
+        ));
+    }
+
     #[test]
     fn test_load_account_config_all_valid() {
         let conf = load_account_config("./secret", false);
         let empty_acc_info = AccountConfig::new();
-        let conf = conf.unwrap_or(AccountConfig::new());
+        let conf = conf.unwrap_or_default();
 
         assert_ne!(conf, empty_acc_info);
     }
@@ -442,13 +1039,23 @@ mod unit_test {
 
     #[test]
     fn test_issue_request_token() {
-        let mut kis = setup();
+        let kis = setup();
         let res = kis.issue_access_token();
         assert!(res.is_ok())
     }
 
+    #[test]
+    fn test_issue_approval_key() {
+        let kis = setup();
+        let res = kis.issue_approval_key();
+        assert!(res.is_ok());
+
+        // 캐시된 접속키 재사용
+        assert_eq!(kis.issue_approval_key().unwrap(), res.unwrap());
+    }
+
     /// 국내주식시세
-    fn run_price_req(f: fn(&KisApi, &str) -> KisResult<serde_json::Value>, ticker: &str) -> serde_json::Value {
+    fn run_price_req<T>(f: fn(&KisApi, &str) -> KisResult<T>, ticker: &str) -> T {
         let kis = setup();
 
         let res = f(&kis, ticker);
@@ -506,27 +1113,65 @@ mod unit_test {
         }
     }
 
+    #[test]
+    fn test_get_buying_power() {
+        let kis = setup();
+
+        let res = kis.get_buying_power(TICKER, OrderType::Market, 0);
+        assert!(res.is_ok());
+        if let Ok(v) = res {
+            println!("Response Text  : {:#?}", v);
+        }
+    }
+
+    #[test]
+    fn test_get_executions() {
+        let kis = setup();
+
+        let res = kis.get_executions(&ExecutionQuery::today());
+        assert!(res.is_ok());
+        if let Ok(v) = res {
+            println!("Response Text  : {:#?}", v);
+        }
+    }
+
     #[test]
     fn test_order_buy() {
         let kis = setup();
 
-        let res = kis.order_buy_stock(TICKER, "01", 1, 0);
+        let res = kis.order_buy_stock(TICKER, OrderType::Market, 1, 0);
         assert!(res.is_ok());
 
         if let Ok(v) = res {
-            println!("Response Text  : {}", v);
+            println!("Response Text  : {:?}", v);
         }
     }
 
+    #[test]
+    fn test_order_modify_cancel() {
+        let kis = setup();
+
+        let price = kis.get_stock_current_price(TICKER).unwrap().lower_limit as u32;
+        let res = kis.order_buy_stock(TICKER, OrderType::Limit, 2, price);
+        assert!(res.is_ok());
+        let ack = res.unwrap();
+
+        let res = kis.modify_order(&ack, OrderType::Limit, OrderQuantity::Partial(1), price);
+        assert!(res.is_ok());
+
+        let res = kis.cancel_order(&ack, OrderQuantity::AllRemaining);
+        assert!(res.is_ok());
+    }
+
     #[test]
     fn test_order_sell() {
         let kis = setup();
 
-        let res = kis.order_sell_stock(TICKER, "01", 1, 0);
+        let res = kis.order_sell_stock(TICKER, OrderType::Market, 1, 0);
         assert!(res.is_ok());
 
         if let Ok(v) = res {
-            println!("Response Text  : {}", v);
+            println!("Response Text  : {:?}", v);
         }
     }
 }
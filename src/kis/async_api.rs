@@ -0,0 +1,724 @@
+//! tokio 기반 async KIS REST API (`async` feature)
+//!
+//! 요청 구성과 응답 처리는 blocking `KisApi` 와 같은 `client` 모듈을 사용한다.
+
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use super::client::{self, configure_client, Call, TokenKind};
+use super::endpoint::Endpoint;
+use super::order::{CreditType, OrderAck, OrderQuantity, OrderRequest, OrderSide, OrderType};
+use super::quotation::{
+    BidAskPrices, Conclusion, CurrentPrice, DailyPrice, InvestorTrading, MemberTrading,
+};
+use super::response::{merge_page, parse_response, KisResponse};
+use super::retry::{OrderRetry, Retry};
+use super::{AccessToken, AccountBalance, AccountConfig, BuyingPower, Execution, ExecutionQuery};
+use super::{
+    DailyProfit, ProfitQuery, ProfitReport, Reservation, ReservationAck, ReservationOrder,
+    TradeProfit,
+};
+use super::{KisRequest, KisResult, Priority, RateLimiter, RequestType, RetryPolicy};
+
+pub struct AsyncKisApi {
+    account_info: AccountConfig,
+    client: Client,
+    access_token: RwLock<AccessToken>,
+    approval_key: RwLock<AccessToken>,
+    rate_limiter: Arc<RateLimiter>,
+    retry_policy: RetryPolicy,
+}
+
+impl AsyncKisApi {
+    /// 기본 설정의 HTTP client 로 생성
+    ///
+    /// # Panics
+    /// TLS backend 초기화에 실패하면 panic (`reqwest::Client::new` 와 동일)
+    pub fn new(account_info: AccountConfig) -> Self {
+        let client = default_client_builder()
+            .build()
+            .expect("failed to build HTTP client");
+        Self::with_client(account_info, client)
+    }
+
+    /// 호출자가 설정한 HTTP client 를 사용, 캐시된 token 이 유효하면 재사용
+    pub fn with_client(account_info: AccountConfig, client: Client) -> Self {
+        let access_token = TokenKind::Access.load_cached(&account_info);
+        let approval_key = TokenKind::Approval.load_cached(&account_info);
+
+        let rate_limiter = Arc::new(RateLimiter::for_account(&account_info));
+
+        Self {
+            account_info,
+            client,
+            access_token: RwLock::new(access_token),
+            approval_key: RwLock::new(approval_key),
+            rate_limiter,
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    /// 조회 요청과 `order_stock_with_retry` 의 재시도 정책
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// 다른 client 와 초당 호출 한도를 공유 (같은 appkey 를 쓰는 `KisApi`, `AsyncKisApi` 등)
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    pub fn rate_limiter(&self) -> &Arc<RateLimiter> {
+        &self.rate_limiter
+    }
+
+    pub fn account_info(&self) -> &AccountConfig {
+        &self.account_info
+    }
+
+    pub async fn get_hashkey(
+        &self,
+        parameters: &[(&str, &str)],
+    ) -> KisResult<(HashMap<String, String>, String)> {
+        let call = client::hashkey(parameters);
+        let ret = call.ep.parameters.iter().cloned().collect();
+        Ok((ret, self.execute(call).await?))
+    }
+
+    /// 보안인증키 발급, 유효한 token 이 있으면 재사용
+    pub async fn issue_access_token(&self) -> KisResult<bool> {
+        self.issue(TokenKind::Access).await?;
+        Ok(true)
+    }
+
+    pub async fn is_access_token_valid(&self) -> bool {
+        self.access_token.read().await.is_valid()
+    }
+
+    /// 접근토큰폐기, 종료 시 호출하며 캐시된 token 도 삭제
+    pub async fn revoke_access_token(&self) -> KisResult<()> {
+        let mut access_token = self.access_token.write().await;
+        if access_token.get_token().is_empty() {
+            return Ok(());
+        }
+
+        let call = client::revoke_access_token(access_token.get_token());
+        call.parse(self.send_oauth(&call.ep).await?.body)?;
+
+        *access_token = AccessToken::default();
+        if let Some(path) = TokenKind::Access.cache_path(&self.account_info) {
+            blocking_io(move || client::remove_cache(&path)).await?;
+        }
+
+        Ok(())
+    }
+
+    /// 실시간 (웹소켓) 접속키 발급, 유효한 접속키가 있으면 재사용
+    pub async fn issue_approval_key(&self) -> KisResult<String> {
+        self.issue(TokenKind::Approval).await
+    }
+
+    fn token(&self, kind: TokenKind) -> &RwLock<AccessToken> {
+        match kind {
+            TokenKind::Access => &self.access_token,
+            TokenKind::Approval => &self.approval_key,
+        }
+    }
+
+    /// 유효한 token 이 없으면 발급받아 캐시
+    async fn issue(&self, kind: TokenKind) -> KisResult<String> {
+        let lock = self.token(kind);
+        {
+            let token = lock.read().await;
+            if token.is_valid() {
+                return Ok(token.get_token().to_string());
+            }
+        }
+
+        let mut token = lock.write().await;
+        // 다른 task 에서 이미 재발급 했을 수 있음
+        if token.is_valid() {
+            return Ok(token.get_token().to_string());
+        }
+
+        let json_data = self.send_oauth(&kind.endpoint()).await?.body;
+
+        *token = kind.parse(&json_data)?;
+        if let Some(path) = kind.cache_path(&self.account_info) {
+            let cached = token.clone();
+            blocking_io(move || cached.save(&path)).await?;
+        }
+
+        Ok(token.get_token().to_string())
+    }
+
+    /// 요청을 보내고 응답을 결과 type 으로 변환
+    async fn execute<T>(&self, call: Call<T>) -> KisResult<T> {
+        let v = if call.all_pages {
+            self.call_all_pages(&call.ep).await?
+        } else {
+            self.call(&call.ep).await?
+        };
+        call.parse(v)
+    }
+
+    /// 요청 전송, 응답 body 만 사용
+    async fn call(&self, ep: &Endpoint) -> KisResult<Value> {
+        Ok(self.call_response(ep).await?.body)
+    }
+
+    /// 연속조회 page 를 모두 조회해 하나의 응답으로 합침
+    async fn call_all_pages(&self, ep: &Endpoint) -> KisResult<Value> {
+        let mut acc = Value::Null;
+        let mut pages = self.pages(ep.clone());
+        while let Some(page) = pages.next_page().await {
+            merge_page(&mut acc, page?);
+        }
+        Ok(acc)
+    }
+
+    fn pages(&self, ep: Endpoint) -> Pages<'_> {
+        Pages {
+            kis: self,
+            next: Some(ep),
+        }
+    }
+
+    /// 조회(GET) 요청은 일시적인 실패를 `RetryPolicy` 에 따라 재시도
+    async fn call_response(&self, ep: &Endpoint) -> KisResult<KisResponse> {
+        if ep.req_type != RequestType::GET {
+            return self.call_once(ep).await;
+        }
+
+        let mut retry = Retry::new(&self.retry_policy);
+        loop {
+            match self.call_once(ep).await {
+                Ok(res) => return Ok(res),
+                Err(e) => tokio::time::sleep(retry.on_error(e)?).await,
+            }
+        }
+    }
+
+    /// 주문은 hashkey 를 먼저 발급받아 header 에 추가
+    async fn call_once(&self, ep: &Endpoint) -> KisResult<KisResponse> {
+        let hashkey = if ep.hashkey {
+            let call = client::hashkey(&ep.parameters);
+            Some(call.parse(self.send(&call.ep, None).await?.body)?)
+        } else {
+            None
+        };
+
+        self.send(ep, hashkey.as_deref()).await
+    }
+
+    /// OAuth 요청이 아니면 access token 추가
+    async fn send(&self, ep: &Endpoint, hashkey: Option<&str>) -> KisResult<KisResponse> {
+        if ep.is_oauth() {
+            return self.send_oauth(ep).await;
+        }
+        let access_token = self.issue(TokenKind::Access).await?;
+        let req = ep.to_request(&self.account_info, Some(&access_token), hashkey)?;
+        self.send_request(req).await
+    }
+
+    /// OAuth 요청은 access token 없이 전송
+    async fn send_oauth(&self, ep: &Endpoint) -> KisResult<KisResponse> {
+        let req = ep.to_request(&self.account_info, None, None)?;
+        self.send_request(req).await
+    }
+
+    async fn send_request(&self, req: KisRequest) -> KisResult<KisResponse> {
+        // 주문은 시세 조회보다 먼저 token 을 받음
+        self.rate_limiter
+            .acquire_async(Priority::from(req.req_type))
+            .await;
+
+        let client = &self.client;
+
+        let res = if let RequestType::GET = req.req_type {
+            client
+                .get(req.url)
+                .headers(req.headers)
+                .query(&req.parameters)
+                .send()
+                .await?
+        } else {
+            client
+                .post(req.url)
+                .headers(req.headers)
+                .json(&req.parameters)
+                .send()
+                .await?
+        };
+
+        let status = res.status();
+        let tr_cont = client::tr_cont(res.headers());
+        Ok(KisResponse {
+            body: parse_response(status, &res.text().await?)?,
+            tr_cont,
+        })
+    }
+
+    /// 주식잔고조회, 모든 page 의 보유 종목과 계좌 합계
+    pub async fn get_account_balance(&self) -> KisResult<AccountBalance> {
+        self.execute(client::account_balance(&self.account_info))
+            .await
+    }
+
+    /// 주식잔고조회 page 단위 조회
+    pub fn get_account_balance_pages(&self) -> Pages<'_> {
+        self.pages(client::account_balance(&self.account_info).ep)
+    }
+
+    /// 매수가능조회 : `order_type`, `price` 로 주문할 때 주문가능현금과 최대 수량
+    ///
+    /// 시장가 계열 주문은 `price` 0 으로 조회하며 현재가 기준으로 계산된다.
+    pub async fn get_buying_power(
+        &self,
+        ticker: &str,
+        order_type: OrderType,
+        price: u32,
+    ) -> KisResult<BuyingPower> {
+        self.execute(client::buying_power(
+            &self.account_info,
+            ticker,
+            order_type,
+            price,
+        )?)
+        .await
+    }
+
+    /// 신용매수가능조회 (실전투자 전용) : `credit_type` 으로 매수할 때 주문가능현금과 최대 수량
+    pub async fn get_credit_buying_power(
+        &self,
+        ticker: &str,
+        order_type: OrderType,
+        price: u32,
+        credit_type: CreditType,
+    ) -> KisResult<BuyingPower> {
+        self.execute(client::credit_buying_power(
+            &self.account_info,
+            ticker,
+            order_type,
+            price,
+            credit_type,
+        )?)
+        .await
+    }
+
+    pub async fn order_buy_stock(
+        &self,
+        ticker: &str,
+        order_type: OrderType,
+        count: u32,
+        price: u32,
+    ) -> KisResult<OrderAck> {
+        let order = client::simple_order(OrderSide::Buy, ticker, order_type, count, price)?;
+        self.order_stock(&order).await
+    }
+
+    pub async fn order_sell_stock(
+        &self,
+        ticker: &str,
+        order_type: OrderType,
+        count: u32,
+        price: u32,
+    ) -> KisResult<OrderAck> {
+        let order = client::simple_order(OrderSide::Sell, ticker, order_type, count, price)?;
+        self.order_stock(&order).await
+    }
+
+    pub async fn order_stock(&self, order: &OrderRequest) -> KisResult<OrderAck> {
+        self.execute(client::order_stock(&self.account_info, order)?)
+            .await
+    }
+
+    /// 주식주문(현금/신용), 일시적인 실패는 당일 주문 조회로 접수 여부를 확인한 뒤 재시도
+    ///
+    /// blocking `KisApi::order_stock_with_retry` 와 같은 방식
+    pub async fn order_stock_with_retry(&self, order: &OrderRequest) -> KisResult<OrderAck> {
+        let call = client::order_stock(&self.account_info, order)?;
+        let history = OrderRetry::history(order);
+        let executions = self.get_executions(&history).await?;
+        let mut retry = OrderRetry::new(order, &self.retry_policy, &executions);
+
+        loop {
+            let err = match self.call(&call.ep).await {
+                Ok(v) => return call.parse(v),
+                Err(e) => e,
+            };
+            let pending = retry.on_failure(err)?;
+            tokio::time::sleep(pending.wait).await;
+
+            // 포기하기 전에도 접수 여부 확인
+            let executions = self.get_executions(&history).await;
+            if let Some(ack) = retry.resolve(executions, pending)? {
+                return Ok(ack);
+            }
+        }
+    }
+
+    /// 주식주문(정정), 정정 주문의 새 주문번호를 돌려줌
+    pub async fn modify_order(
+        &self,
+        order: &OrderAck,
+        order_type: OrderType,
+        quantity: OrderQuantity,
+        price: u32,
+    ) -> KisResult<OrderAck> {
+        self.execute(client::modify_order(
+            &self.account_info,
+            order,
+            order_type,
+            quantity,
+            price,
+        )?)
+        .await
+    }
+
+    /// 주식주문(취소), 취소 주문의 주문번호를 돌려줌
+    pub async fn cancel_order(
+        &self,
+        order: &OrderAck,
+        quantity: OrderQuantity,
+    ) -> KisResult<OrderAck> {
+        self.execute(client::cancel_order(&self.account_info, order, quantity)?)
+            .await
+    }
+
+    /// 주식예약주문 (실전투자 전용), 다음 영업일 장 시작 전에 전송
+    pub async fn order_reservation(&self, order: &ReservationOrder) -> KisResult<ReservationAck> {
+        self.execute(client::order_reservation(&self.account_info, order)?)
+            .await
+    }
+
+    /// 주식예약주문정정 (실전투자 전용), 예약 내용을 `order` 로 변경
+    pub async fn modify_reservation(
+        &self,
+        reservation: &Reservation,
+        order: &ReservationOrder,
+    ) -> KisResult<()> {
+        self.execute(client::modify_reservation(
+            &self.account_info,
+            reservation,
+            order,
+        )?)
+        .await
+    }
+
+    /// 주식예약주문취소 (실전투자 전용)
+    pub async fn cancel_reservation(&self, reservation: &Reservation) -> KisResult<()> {
+        self.execute(client::cancel_reservation(&self.account_info, reservation)?)
+            .await
+    }
+
+    /// 주식예약주문조회 (실전투자 전용), 예약주문일자 `start_date` ~ `end_date` (YYYYMMDD)
+    pub async fn get_reservations(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> KisResult<Vec<Reservation>> {
+        self.execute(client::reservations(
+            &self.account_info,
+            start_date,
+            end_date,
+        )?)
+        .await
+    }
+
+    /// 주식일별주문체결조회, 모든 page 의 주문별 체결 현황
+    pub async fn get_executions(&self, query: &ExecutionQuery) -> KisResult<Vec<Execution>> {
+        self.execute(client::executions(&self.account_info, query)?)
+            .await
+    }
+
+    /// 주식일별주문체결조회 page 단위 조회
+    pub fn get_executions_pages(&self, query: &ExecutionQuery) -> KisResult<Pages<'_>> {
+        Ok(self.pages(client::executions(&self.account_info, query)?.ep))
+    }
+
+    /// 주식정정취소가능주문조회, 모든 page 의 output 을 합친 응답
+    pub async fn get_ordered_list(&self) -> KisResult<Value> {
+        self.execute(client::ordered_list(&self.account_info)?)
+            .await
+    }
+
+    /// 주식정정취소가능주문조회 page 단위 조회
+    pub fn get_ordered_list_pages(&self) -> KisResult<Pages<'_>> {
+        Ok(self.pages(client::ordered_list(&self.account_info)?.ep))
+    }
+
+    /// 기간별매매손익현황조회 (실전투자 전용), 매매일자별 종목 실현손익과 기간 합계
+    pub async fn get_trade_profits(
+        &self,
+        query: &ProfitQuery,
+    ) -> KisResult<ProfitReport<TradeProfit>> {
+        self.execute(client::trade_profits(&self.account_info, query)?)
+            .await
+    }
+
+    /// 기간별손익일별합산조회 (실전투자 전용), 일자별 실현손익과 기간 합계
+    pub async fn get_daily_profits(
+        &self,
+        query: &ProfitQuery,
+    ) -> KisResult<ProfitReport<DailyProfit>> {
+        self.execute(client::daily_profits(&self.account_info, query)?)
+            .await
+    }
+
+    // 국내주식시세
+    /// 주식현재가 시세[v1_국내주식-008]
+    pub async fn get_stock_current_price(&self, ticker: &str) -> KisResult<CurrentPrice> {
+        self.execute(client::stock_current_price(ticker)).await
+    }
+
+    /// 주식현재가 체결[v1_국내주식-009]
+    pub async fn get_stock_current_concluded(&self, ticker: &str) -> KisResult<Vec<Conclusion>> {
+        self.execute(client::stock_current_concluded(ticker)).await
+    }
+
+    /// 주식현재가 일자별[v1_국내주식-010]
+    pub async fn get_stock_daily_price(&self, ticker: &str) -> KisResult<Vec<DailyPrice>> {
+        self.execute(client::stock_daily_price(ticker)).await
+    }
+
+    /// 주식현재가 호가 예상체결[v1_국내주식-011]
+    pub async fn get_stock_bid_ask_prices(&self, ticker: &str) -> KisResult<BidAskPrices> {
+        self.execute(client::stock_bid_ask_prices(ticker)).await
+    }
+
+    /// 주식현재가 투자자[v1_국내주식-012]
+    pub async fn get_stock_investor_list(&self, ticker: &str) -> KisResult<Vec<InvestorTrading>> {
+        self.execute(client::stock_investor_list(ticker)).await
+    }
+
+    /// 주식현재가 회원사[v1_국내주식-013]
+    pub async fn get_stock_membership_list(&self, ticker: &str) -> KisResult<MemberTrading> {
+        self.execute(client::stock_membership_list(ticker)).await
+    }
+
+    /// ELW현재가 시세[v1_국내주식-014]
+    pub async fn get_stock_elw_price(&self, ticker: &str) -> KisResult<Value> {
+        self.execute(client::stock_elw_price(ticker)).await
+    }
+
+    /// 국내주식기간별시세(일/주/월/년)[v1_국내주식-016]
+    pub async fn get_stock_duration_prices(
+        &self,
+        ticker: &str,
+        begin: &str,
+        end: &str,
+        duration: &str,
+    ) -> KisResult<Value> {
+        self.execute(client::stock_duration_prices(ticker, begin, end, duration))
+            .await
+    }
+
+    /// 국내주식업종기간별시세(일/주/월/년)[v1_국내주식-021]
+    pub async fn get_sector_duration_prices(
+        &self,
+        section: &str,
+        begin: &str,
+        end: &str,
+        duration: &str,
+    ) -> KisResult<Value> {
+        self.execute(client::sector_duration_prices(
+            section, begin, end, duration,
+        ))
+        .await
+    }
+}
+
+/// 연속조회, 응답의 tr_cont 를 따라 `next_page` 로 다음 page 를 조회
+///
+/// 에러가 나면 그 에러를 돌려주고 끝난다.
+pub struct Pages<'a> {
+    kis: &'a AsyncKisApi,
+    next: Option<Endpoint>,
+}
+
+impl Pages<'_> {
+    /// 다음 page, 마지막 page 이후에는 `None`
+    pub async fn next_page(&mut self) -> Option<KisResult<Value>> {
+        let ep = self.next.take()?;
+        match self.kis.call_response(&ep).await {
+            Ok(res) => {
+                self.next = ep.next_page(&res);
+                Some(Ok(res.body))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// token 캐시 파일 쓰기, 삭제는 runtime thread 를 막지 않도록 blocking thread 에서 실행
+async fn blocking_io<F>(f: F) -> KisResult<()>
+where
+    F: FnOnce() -> KisResult<()> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(io::Error::other)?
+}
+
+/// AsyncKisApi 기본 HTTP client 설정, blocking `default_client_builder` 와 같은 값
+pub fn default_client_builder() -> reqwest::ClientBuilder {
+    configure_client!(Client::builder())
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use crate::kis::mock_server::{MockResponse, MockServer};
+    use std::sync::Arc;
+
+    fn mock_price(ticker: &str) -> String {
+        let price = ticker.parse::<i64>().unwrap();
+        format!(
+            r#"{{"rt_cd":"0","msg_cd":"MCA00000","msg1":"정상처리 되었습니다.","output":{{"stck_prpr":"{price}","prdy_vrss":"0","prdy_ctrt":"0.00","acml_vol":"0","acml_tr_pbmn":"0","stck_oprc":"{price}","stck_hgpr":"{price}","stck_lwpr":"{price}","stck_mxpr":"0","stck_llam":"0","stck_sdpr":"{price}"}}}}"#
+        )
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_concurrent_price_polling() {
+        let server = MockServer::start(|req| {
+            let ticker = req
+                .query
+                .split('&')
+                .find_map(|kv| kv.strip_prefix("fid_input_iscd="))
+                .unwrap_or("000000")
+                .to_string();
+            assert_eq!(req.header("authorization"), Some("Bearer mock-token"));
+            MockResponse::ok(&mock_price(&ticker))
+        });
+        let kis = Arc::new(AsyncKisApi::new(AccountConfig::mock(server.url(), false)));
+
+        let tickers: [&'static str; 4] = ["005930", "000660", "003490", "035720"];
+        let handles: Vec<_> = tickers
+            .iter()
+            .map(|&ticker| {
+                let kis = kis.clone();
+                tokio::spawn(async move { kis.get_stock_current_price(ticker).await })
+            })
+            .collect();
+
+        for (ticker, handle) in tickers.iter().zip(handles) {
+            let price = handle.await.unwrap().unwrap();
+            assert_eq!(price.price, ticker.parse::<i64>().unwrap());
+        }
+        assert_eq!(server.requests(), tickers.len());
+    }
+
+    #[tokio::test]
+    async fn test_order_with_hashkey() {
+        let server = MockServer::start(|req| match req.path.as_str() {
+            "/uapi/hashkey" => MockResponse::ok(r#"{"HASH":"mock-hash"}"#),
+            _ => {
+                assert_eq!(req.header("hashkey"), Some("mock-hash"));
+                assert_eq!(req.header("tr_id"), Some("VTTC0802U"));
+                MockResponse::ok(
+                    r#"{"rt_cd":"0","msg_cd":"APBK0013","msg1":"주문 전송 완료 되었습니다.","output":{"KRX_FWDG_ORD_ORGNO":"91252","ODNO":"0000117057","ORD_TMD":"121052"}}"#,
+                )
+            }
+        });
+        let kis = AsyncKisApi::new(AccountConfig::mock(server.url(), false));
+
+        let order = OrderRequest::buy("005930")
+            .quantity(1)
+            .price(70000)
+            .build()
+            .unwrap();
+        let ack = kis.order_stock(&order).await.unwrap();
+        assert_eq!(ack.order_no, "0000117057");
+        assert_eq!(server.requests(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_executions_pages() {
+        let server = MockServer::start(|req| {
+            assert_eq!(
+                req.path,
+                "/uapi/domestic-stock/v1/trading/inquire-daily-ccld"
+            );
+            let first = !req.query.contains("CTX_AREA_NK100=P2");
+            let odno = if first { "0000117057" } else { "0000117058" };
+            let body = serde_json::json!({
+                "rt_cd": "0", "msg_cd": "", "msg1": "",
+                "ctx_area_fk100": "F", "ctx_area_nk100": "P2",
+                "output1": [{"ord_dt": "20240105", "pdno": "005930", "sll_buy_dvsn_cd": "02", "ord_dvsn_cd": "00", "ord_qty": "10", "ord_unpr": "70000", "ord_gno_brno": "91252", "odno": odno, "tot_ccld_qty": "10"}],
+                "output2": {"tot_ord_qty": "20", "tot_ccld_qty": "20"}
+            });
+            MockResponse::ok(&body.to_string()).header("tr_cont", if first { "M" } else { "D" })
+        });
+        let kis = AsyncKisApi::new(AccountConfig::mock(server.url(), false))
+            .with_rate_limiter(Arc::new(RateLimiter::new(u32::MAX)));
+        let query = ExecutionQuery::new("20240102", "20240105");
+
+        let mut pages = kis.get_executions_pages(&query).unwrap();
+        let mut orders = Vec::new();
+        while let Some(page) = pages.next_page().await {
+            orders.push(page.unwrap()["output1"][0]["odno"].clone());
+        }
+        assert_eq!(orders, ["0000117057", "0000117058"]);
+
+        let executions = kis.get_executions(&query).await.unwrap();
+        assert_eq!(executions.len(), 2);
+        assert_eq!(server.requests(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_hashkey_and_duration_prices() {
+        let server = MockServer::start(|req| match req.path.as_str() {
+            "/uapi/hashkey" => {
+                assert_eq!(req.body, r#"{"CANO":"00000000"}"#);
+                MockResponse::ok(r#"{"HASH":"mock-hash"}"#)
+            }
+            "/uapi/domestic-stock/v1/quotations/inquire-daily-itemchartprice" => {
+                assert!(req.query.contains("fid_input_iscd=005930"));
+                assert!(req.query.contains("fid_period_div_code=D"));
+                MockResponse::ok(r#"{"rt_cd":"0","msg_cd":"","msg1":"","output2":[]}"#)
+            }
+            path => panic!("unexpected path {path}"),
+        });
+        let kis = AsyncKisApi::new(AccountConfig::mock(server.url(), false))
+            .with_rate_limiter(Arc::new(RateLimiter::new(u32::MAX)));
+
+        let (parameters, hash) = kis.get_hashkey(&[("CANO", "00000000")]).await.unwrap();
+        assert_eq!(parameters["CANO"], "00000000");
+        assert_eq!(hash, "mock-hash");
+
+        let v = kis
+            .get_stock_duration_prices("005930", "20240102", "20240105", "D")
+            .await
+            .unwrap();
+        assert_eq!(v["output2"], serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_token_cache() {
+        let server = MockServer::start(|req| {
+            assert_eq!(req.path, "/oauth2/revokeP");
+            MockResponse::ok(r#"{"code":200,"message":"접근토큰 폐기에 성공하였습니다"}"#)
+        });
+        let dir = format!("target/async_token_cache_{}", std::process::id());
+        std::fs::create_dir_all(&dir).unwrap();
+        let conf = serde_json::to_string(&AccountConfig::mock(server.url(), false)).unwrap();
+        std::fs::write(format!("{dir}/kis_test.json"), conf).unwrap();
+        let conf = crate::kis::load_account_config(&dir, false).unwrap();
+        let cache = conf.token_cache_path().unwrap();
+
+        let kis = AsyncKisApi::new(conf);
+        assert!(kis.issue_access_token().await.unwrap());
+        assert!(cache.exists());
+        kis.revoke_access_token().await.unwrap();
+        assert!(!cache.exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
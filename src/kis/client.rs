@@ -0,0 +1,439 @@
+//! `KisApi`, `AsyncKisApi` 공통 : HTTP client 설정, token 발급, 요청별 endpoint 와 응답 type
+//!
+//! 두 client 는 전송 (blocking / async) 만 각자 구현하고 요청 구성과 응답 해석은 여기서 한다.
+
+use reqwest::header::HeaderMap;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::endpoint::{self, Endpoint};
+use super::order::{
+    validate_price, validate_ticker, CreditType, OrderAck, OrderQuantity, OrderRequest, OrderSide,
+    OrderType,
+};
+use super::quotation::{
+    BidAskPrices, Conclusion, CurrentPrice, DailyPrice, InvestorTrading, MemberTrading,
+};
+use super::response::take_output;
+use super::{AccessToken, AccountBalance, AccountConfig, BuyingPower, Execution, ExecutionQuery};
+use super::{
+    DailyProfit, ProfitQuery, ProfitReport, Reservation, ReservationAck, ReservationOrder,
+    TradeProfit,
+};
+use super::{Error, KisResult};
+
+/// 응답 대기 시간
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// 연결 대기 시간
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// 사용하지 않는 연결을 pool 에 유지하는 시간
+pub const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+/// HTTP client user agent
+pub const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// 기본 HTTP client 설정 : keep-alive, connection pool, timeout, user agent
+///
+/// blocking 과 async `ClientBuilder` 는 type 이 달라 macro 로 같은 설정을 적용
+macro_rules! configure_client {
+    ($builder:expr) => {
+        $builder
+            .user_agent($crate::kis::client::USER_AGENT)
+            .timeout($crate::kis::client::REQUEST_TIMEOUT)
+            .connect_timeout($crate::kis::client::CONNECT_TIMEOUT)
+            .pool_idle_timeout($crate::kis::client::POOL_IDLE_TIMEOUT)
+            .tcp_keepalive(Some($crate::kis::client::POOL_IDLE_TIMEOUT))
+            .tcp_nodelay(true)
+    };
+}
+pub(crate) use configure_client;
+
+/// 발급받아 재사용하는 인증 정보
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TokenKind {
+    /// REST API 접근토큰
+    Access,
+    /// 실시간 (웹소켓) 접속키
+    Approval,
+}
+
+impl TokenKind {
+    /// 발급 요청
+    pub fn endpoint(self) -> Endpoint {
+        match self {
+            TokenKind::Access => endpoint::access_token(),
+            TokenKind::Approval => endpoint::approval_key(),
+        }
+    }
+
+    /// 발급 응답
+    pub fn parse(self, json_data: &Value) -> KisResult<AccessToken> {
+        match self {
+            TokenKind::Access => AccessToken::from_token_response(json_data),
+            TokenKind::Approval => AccessToken::from_approval_response(json_data),
+        }
+    }
+
+    /// 설정 파일 옆의 캐시 경로
+    pub fn cache_path(self, conf: &AccountConfig) -> Option<PathBuf> {
+        match self {
+            TokenKind::Access => conf.token_cache_path(),
+            TokenKind::Approval => conf.approval_key_cache_path(),
+        }
+    }
+
+    /// 캐시된 token 이 유효하면 사용, 아니면 빈 token
+    pub fn load_cached(self, conf: &AccountConfig) -> AccessToken {
+        AccessToken::load_valid(self.cache_path(conf))
+    }
+
+    /// blocking client 의 lock 에러
+    pub fn poisoned(self) -> Error {
+        let name = match self {
+            TokenKind::Access => "access token",
+            TokenKind::Approval => "approval key",
+        };
+        Error::Auth(format!("{name} lock poisoned"))
+    }
+}
+
+/// 폐기한 token 의 캐시 파일 삭제, 없으면 무시
+pub(crate) fn remove_cache(path: &Path) -> KisResult<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// 연속조회 여부 (tr_cont header)
+pub(crate) fn tr_cont(headers: &HeaderMap) -> String {
+    headers
+        .get("tr_cont")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// 요청 하나 : endpoint 와 응답 body 를 결과 type 으로 바꾸는 함수
+pub(crate) struct Call<T> {
+    pub ep: Endpoint,
+    /// 연속조회 page 를 모두 조회해 합친 뒤 parse
+    pub all_pages: bool,
+    parse: fn(Value) -> KisResult<T>,
+}
+
+impl<T> Call<T> {
+    fn new(ep: Endpoint, parse: fn(Value) -> KisResult<T>) -> Self {
+        Self {
+            ep,
+            all_pages: false,
+            parse,
+        }
+    }
+
+    fn all_pages(ep: Endpoint, parse: fn(Value) -> KisResult<T>) -> Self {
+        Self {
+            ep,
+            all_pages: true,
+            parse,
+        }
+    }
+
+    pub fn parse(&self, v: Value) -> KisResult<T> {
+        (self.parse)(v)
+    }
+}
+
+fn output<T: DeserializeOwned>(mut v: Value) -> KisResult<T> {
+    take_output(&mut v, "output")
+}
+
+fn output1<T: DeserializeOwned>(mut v: Value) -> KisResult<T> {
+    take_output(&mut v, "output1")
+}
+
+fn body(v: Value) -> KisResult<Value> {
+    Ok(v)
+}
+
+fn ignore(_: Value) -> KisResult<()> {
+    Ok(())
+}
+
+/// hashkey 발급, 주문 body 의 parameter 로 계산
+pub(crate) fn hashkey<K: AsRef<str>, V: AsRef<str>>(parameters: &[(K, V)]) -> Call<String> {
+    Call::new(endpoint::hashkey(parameters), |v| {
+        endpoint::parse_hashkey(&v)
+    })
+}
+
+/// 접근토큰폐기
+pub(crate) fn revoke_access_token(token: &str) -> Call<()> {
+    Call::new(endpoint::revoke_access_token(token), |v| {
+        endpoint::check_revoke_response(&v)
+    })
+}
+
+// 국내주식주문
+
+/// 주식잔고조회
+pub(crate) fn account_balance(conf: &AccountConfig) -> Call<AccountBalance> {
+    Call::all_pages(
+        endpoint::account_balance(conf),
+        AccountBalance::from_response,
+    )
+}
+
+/// 매수가능조회
+pub(crate) fn buying_power(
+    conf: &AccountConfig,
+    ticker: &str,
+    order_type: OrderType,
+    price: u32,
+) -> KisResult<Call<BuyingPower>> {
+    validate_ticker(ticker)?;
+    validate_price(order_type, price)?;
+
+    Ok(Call::new(
+        endpoint::buying_power(conf, ticker, order_type, price),
+        output,
+    ))
+}
+
+/// 신용매수가능조회 (실전투자 전용)
+pub(crate) fn credit_buying_power(
+    conf: &AccountConfig,
+    ticker: &str,
+    order_type: OrderType,
+    price: u32,
+    credit_type: CreditType,
+) -> KisResult<Call<BuyingPower>> {
+    validate_ticker(ticker)?;
+    validate_price(order_type, price)?;
+
+    let ep = endpoint::credit_buying_power(conf, ticker, order_type, price, credit_type)?;
+    Ok(Call::new(ep, output))
+}
+
+/// 수량, 가격만 정한 현금 주문
+pub(crate) fn simple_order(
+    side: OrderSide,
+    ticker: &str,
+    order_type: OrderType,
+    count: u32,
+    price: u32,
+) -> KisResult<OrderRequest> {
+    let builder = match side {
+        OrderSide::Buy => OrderRequest::buy(ticker),
+        OrderSide::Sell => OrderRequest::sell(ticker),
+    };
+    builder
+        .order_type(order_type)
+        .quantity(count)
+        .price(price)
+        .build()
+}
+
+/// 주식주문(현금/신용)
+pub(crate) fn order_stock(conf: &AccountConfig, order: &OrderRequest) -> KisResult<Call<OrderAck>> {
+    Ok(Call::new(endpoint::order_stock(conf, order)?, output))
+}
+
+/// 주식주문(정정)
+pub(crate) fn modify_order(
+    conf: &AccountConfig,
+    order: &OrderAck,
+    order_type: OrderType,
+    quantity: OrderQuantity,
+    price: u32,
+) -> KisResult<Call<OrderAck>> {
+    validate_price(order_type, price)?;
+    let ep = endpoint::order_revise(conf, order, "01", order_type, quantity, price)?;
+    Ok(Call::new(ep, output))
+}
+
+/// 주식주문(취소), 취소 주문은 주문구분, 단가를 사용하지 않음
+pub(crate) fn cancel_order(
+    conf: &AccountConfig,
+    order: &OrderAck,
+    quantity: OrderQuantity,
+) -> KisResult<Call<OrderAck>> {
+    let ep = endpoint::order_revise(conf, order, "02", OrderType::Limit, quantity, 0)?;
+    Ok(Call::new(ep, output))
+}
+
+/// 주식예약주문 (실전투자 전용)
+pub(crate) fn order_reservation(
+    conf: &AccountConfig,
+    order: &ReservationOrder,
+) -> KisResult<Call<ReservationAck>> {
+    Ok(Call::new(endpoint::order_reservation(conf, order)?, output))
+}
+
+/// 주식예약주문정정 (실전투자 전용)
+pub(crate) fn modify_reservation(
+    conf: &AccountConfig,
+    reservation: &Reservation,
+    order: &ReservationOrder,
+) -> KisResult<Call<()>> {
+    let ep = endpoint::revise_reservation(conf, reservation, order)?;
+    Ok(Call::new(ep, ignore))
+}
+
+/// 주식예약주문취소 (실전투자 전용)
+pub(crate) fn cancel_reservation(
+    conf: &AccountConfig,
+    reservation: &Reservation,
+) -> KisResult<Call<()>> {
+    let ep = endpoint::cancel_reservation(conf, reservation)?;
+    Ok(Call::new(ep, ignore))
+}
+
+/// 주식예약주문조회 (실전투자 전용)
+pub(crate) fn reservations(
+    conf: &AccountConfig,
+    start_date: &str,
+    end_date: &str,
+) -> KisResult<Call<Vec<Reservation>>> {
+    let ep = endpoint::reservations(conf, start_date, end_date)?;
+    Ok(Call::all_pages(ep, output))
+}
+
+/// 주식일별주문체결조회
+pub(crate) fn executions(
+    conf: &AccountConfig,
+    query: &ExecutionQuery,
+) -> KisResult<Call<Vec<Execution>>> {
+    let ep = endpoint::daily_executions(conf, query)?;
+    Ok(Call::all_pages(ep, output1))
+}
+
+/// 주식정정취소가능주문조회
+pub(crate) fn ordered_list(conf: &AccountConfig) -> KisResult<Call<Value>> {
+    Ok(Call::all_pages(endpoint::ordered_list(conf)?, body))
+}
+
+/// 기간별매매손익현황조회 (실전투자 전용)
+pub(crate) fn trade_profits(
+    conf: &AccountConfig,
+    query: &ProfitQuery,
+) -> KisResult<Call<ProfitReport<TradeProfit>>> {
+    let ep = endpoint::trade_profits(conf, query)?;
+    Ok(Call::all_pages(ep, ProfitReport::from_response))
+}
+
+/// 기간별손익일별합산조회 (실전투자 전용)
+pub(crate) fn daily_profits(
+    conf: &AccountConfig,
+    query: &ProfitQuery,
+) -> KisResult<Call<ProfitReport<DailyProfit>>> {
+    let ep = endpoint::daily_profits(conf, query)?;
+    Ok(Call::all_pages(ep, ProfitReport::from_response))
+}
+
+// 국내주식시세
+
+/// 주식현재가 시세
+pub(crate) fn stock_current_price(ticker: &str) -> Call<CurrentPrice> {
+    Call::new(endpoint::stock_current_price(ticker), output)
+}
+
+/// 주식현재가 체결
+pub(crate) fn stock_current_concluded(ticker: &str) -> Call<Vec<Conclusion>> {
+    Call::new(endpoint::stock_current_concluded(ticker), output)
+}
+
+/// 주식현재가 일자별
+pub(crate) fn stock_daily_price(ticker: &str) -> Call<Vec<DailyPrice>> {
+    Call::new(endpoint::stock_daily_price(ticker), output)
+}
+
+/// 주식현재가 호가 예상체결
+pub(crate) fn stock_bid_ask_prices(ticker: &str) -> Call<BidAskPrices> {
+    Call::new(
+        endpoint::stock_bid_ask_prices(ticker),
+        BidAskPrices::from_response,
+    )
+}
+
+/// 주식현재가 투자자
+pub(crate) fn stock_investor_list(ticker: &str) -> Call<Vec<InvestorTrading>> {
+    Call::new(endpoint::stock_investor_list(ticker), output)
+}
+
+/// 주식현재가 회원사
+pub(crate) fn stock_membership_list(ticker: &str) -> Call<MemberTrading> {
+    Call::new(endpoint::stock_membership_list(ticker), output)
+}
+
+/// ELW현재가 시세
+pub(crate) fn stock_elw_price(ticker: &str) -> Call<Value> {
+    Call::new(endpoint::stock_elw_price(ticker), body)
+}
+
+/// 국내주식기간별시세(일/주/월/년)
+pub(crate) fn stock_duration_prices(
+    ticker: &str,
+    begin: &str,
+    end: &str,
+    duration: &str,
+) -> Call<Value> {
+    Call::new(
+        endpoint::stock_duration_prices(ticker, begin, end, duration),
+        body,
+    )
+}
+
+/// 국내주식업종기간별시세(일/주/월/년)
+pub(crate) fn sector_duration_prices(
+    section: &str,
+    begin: &str,
+    end: &str,
+    duration: &str,
+) -> Call<Value> {
+    Call::new(
+        endpoint::sector_duration_prices(section, begin, end, duration),
+        body,
+    )
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+
+    #[test]
+    fn test_call_output() {
+        let conf = AccountConfig::mock("http://localhost", false);
+        let call = executions(&conf, &ExecutionQuery::today()).unwrap();
+        assert!(call.all_pages);
+        let v = serde_json::json!({
+            "output1": [{"ord_dt": "20240105", "pdno": "005930", "sll_buy_dvsn_cd": "02", "ord_dvsn_cd": "00", "ord_qty": "10", "ord_unpr": "70000", "ord_gno_brno": "91252", "odno": "0000117057", "tot_ccld_qty": "10"}],
+            "output2": {"tot_ord_qty": "10"}
+        });
+        assert_eq!(call.parse(v).unwrap()[0].order_no, "0000117057");
+
+        let call = stock_elw_price("57JA12");
+        assert!(!call.all_pages);
+        assert_eq!(call.parse(serde_json::json!({"a": 1})).unwrap()["a"], 1);
+
+        assert!(matches!(
+            buying_power(&conf, "5930", OrderType::Limit, 70000),
+            Err(Error::InvalidRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_token_kind() {
+        let conf = AccountConfig::mock("http://localhost", false);
+        assert!(TokenKind::Access.cache_path(&conf).is_none());
+        assert!(!TokenKind::Approval.load_cached(&conf).is_valid());
+        assert!(matches!(
+            TokenKind::Approval.poisoned(),
+            Error::Auth(msg) if msg == "approval key lock poisoned"
+        ));
+        assert!(remove_cache(Path::new("target/no_such_token_cache.json")).is_ok());
+    }
+}
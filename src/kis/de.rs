@@ -0,0 +1,60 @@
+//! KIS 응답의 문자열 숫자 필드("70000", "-1.23", "") 를 위한 serde helper
+
+use std::str::FromStr;
+
+use serde::de::{self, Deserialize, Deserializer};
+use serde_json::Value;
+
+/// 문자열 또는 숫자로 된 값을 `T` 로 변환, 빈 문자열은 `T::default()`
+pub(crate) fn number<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + Default,
+    T::Err: std::fmt::Display,
+{
+    let value = Value::deserialize(deserializer)?;
+    parse_value(&value).map_err(de::Error::custom)
+}
+
+/// `serde_json::Value` 로부터 숫자 변환 (`number` 와 동일한 규칙)
+pub(crate) fn parse_value<T>(value: &Value) -> Result<T, String>
+where
+    T: FromStr + Default,
+    T::Err: std::fmt::Display,
+{
+    match value {
+        Value::String(s) => parse_str(s),
+        Value::Number(n) => parse_str(&n.to_string()),
+        Value::Null => Ok(T::default()),
+        v => Err(format!("expected number or string, found {v}")),
+    }
+}
+
+/// 문자열 숫자 변환, 앞뒤 공백과 앞의 '+' 는 무시
+pub(crate) fn parse_str<T>(s: &str) -> Result<T, String>
+where
+    T: FromStr + Default,
+    T::Err: std::fmt::Display,
+{
+    let s = s.trim();
+    let s = s.strip_prefix('+').unwrap_or(s);
+    if s.is_empty() {
+        return Ok(T::default());
+    }
+    s.parse::<T>()
+        .map_err(|e| format!("invalid number {s:?}: {e}"))
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+
+    #[test]
+    fn test_parse_str() {
+        assert_eq!(parse_str::<i64>("70000"), Ok(70000));
+        assert_eq!(parse_str::<i64>("-300"), Ok(-300));
+        assert_eq!(parse_str::<i64>(""), Ok(0));
+        assert_eq!(parse_str::<f64>(" +1.25 "), Ok(1.25));
+        assert!(parse_str::<u64>("abc").is_err());
+    }
+}
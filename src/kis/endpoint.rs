@@ -0,0 +1,847 @@
+//! KIS REST API 요청 정의
+//!
+//! blocking `KisApi` 와 async `AsyncKisApi` 가 같은 요청 정의를 사용하도록
+//! URL, tr_id, query/body 구성은 여기서만 한다.
+
+use reqwest::header::{self, HeaderName, HeaderValue};
+use serde_json::Value;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::order::{
+    CreditType, Funding, OrderAck, OrderQuantity, OrderRequest, OrderSide, OrderType,
+    ReservationOrder,
+};
+use super::response::KisResponse;
+use super::trading::{validate_period, ExecutionQuery, ProfitQuery, Reservation};
+use super::{AccountConfig, Error, KisRequest, KisResult, RequestType};
+
+/// 전송 전 요청 : access token, hashkey 는 전송할 때 추가
+#[derive(Debug, Clone)]
+pub(crate) struct Endpoint {
+    pub url: &'static str,
+    pub req_type: RequestType,
+    pub headers: Vec<(&'static str, &'static str)>,
+    pub parameters: Vec<(String, String)>,
+    /// body 의 hashkey 를 발급받아 header 에 추가해야 하는지 여부
+    pub hashkey: bool,
+}
+
+impl Endpoint {
+    fn new(url: &'static str, req_type: RequestType) -> Self {
+        Self {
+            url,
+            req_type,
+            headers: Vec::new(),
+            parameters: Vec::new(),
+            hashkey: false,
+        }
+    }
+
+    fn get(url: &'static str, tr_id: &'static str) -> Self {
+        Self::new(url, RequestType::GET).header("tr_id", tr_id)
+    }
+
+    /// 주문 요청 : 개인 고객, hashkey 필요
+    fn order(url: &'static str, tr_id: &'static str) -> Self {
+        let mut ep = Self::new(url, RequestType::POST)
+            .header("custtype", "P")
+            .header("tr_id", tr_id);
+        ep.hashkey = true;
+        ep
+    }
+
+    fn header(mut self, name: &'static str, value: &'static str) -> Self {
+        self.headers.push((name, value));
+        self
+    }
+
+    fn param(mut self, name: &'static str, value: &str) -> Self {
+        self.parameters.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// 계좌번호 (CANO, ACNT_PRDT_CD)
+    fn account(self, conf: &AccountConfig) -> Self {
+        self.param("CANO", conf.get_account_no())
+            .param("ACNT_PRDT_CD", conf.get_account_product_code())
+    }
+
+    pub fn is_oauth(&self) -> bool {
+        self.req_type.is_oauth()
+    }
+
+    /// 연속조회 다음 요청 : tr_cont 가 F/M 이면 응답의 연속조회키(CTX_AREA_*) 로 다시 조회
+    ///
+    /// 연속조회를 지원하지 않거나 키가 바뀌지 않으면 `None`
+    pub fn next_page(&self, res: &KisResponse) -> Option<Endpoint> {
+        if !res.has_next() {
+            return None;
+        }
+
+        let mut next = self.clone();
+        let mut changed = false;
+        for (k, v) in next.parameters.iter_mut() {
+            if !k.starts_with("CTX_AREA_") {
+                continue;
+            }
+            let key = res
+                .body
+                .get(k.to_ascii_lowercase())
+                .or_else(|| res.body.get(k.as_str()))
+                .and_then(Value::as_str)?;
+            if key != v {
+                *v = key.to_string();
+                changed = true;
+            }
+        }
+        if !changed {
+            return None;
+        }
+
+        next.headers.retain(|(k, _)| *k != "tr_cont");
+        next.headers.push(("tr_cont", "N"));
+        Some(next)
+    }
+
+    /// 전송할 `KisRequest` 생성
+    pub fn to_request(
+        &self,
+        conf: &AccountConfig,
+        access_token: Option<&str>,
+        hashkey: Option<&str>,
+    ) -> KisResult<KisRequest> {
+        let mut req = KisRequest::new(self.req_type, conf);
+        if let Some(token) = access_token {
+            let auth_header = format!("Bearer {token}");
+            let value = HeaderValue::from_str(&auth_header)
+                .map_err(|_| Error::Auth("access token is not a valid header value".to_string()))?;
+            req.headers.insert(header::AUTHORIZATION, value);
+        }
+
+        // request URL
+        req.url += self.url;
+
+        // additional headers
+        for (k, v) in self.headers.iter() {
+            req.headers
+                .insert(parse_header_name(k)?, parse_header_value(v)?);
+        }
+        if let Some(hashkey) = hashkey {
+            req.headers
+                .insert(parse_header_name("hashkey")?, parse_header_value(hashkey)?);
+        }
+
+        // parameters : query or body
+        for (k, v) in self.parameters.iter() {
+            req.parameters.insert(k.clone(), v.clone());
+        }
+
+        Ok(req)
+    }
+}
+
+/// 모의투자에서 지원하지 않는 API
+fn real_only(conf: &AccountConfig) -> KisResult<()> {
+    if !conf.is_real() {
+        return Err(Error::Config(
+            "not available for virtual account".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn tr_id(conf: &AccountConfig, real: &'static str, virtual_: &'static str) -> &'static str {
+    if conf.is_real() {
+        real
+    } else {
+        virtual_
+    }
+}
+
+// OAuth인증
+
+/// Hashkey : `parameters` 는 주문 body
+pub(crate) fn hashkey<K: AsRef<str>, V: AsRef<str>>(parameters: &[(K, V)]) -> Endpoint {
+    let mut ep = Endpoint::new("/uapi/hashkey", RequestType::POST);
+    ep.parameters = parameters
+        .iter()
+        .map(|(k, v)| (k.as_ref().to_string(), v.as_ref().to_string()))
+        .collect();
+    ep
+}
+
+/// Hashkey 응답의 HASH 값
+pub(crate) fn parse_hashkey(val: &Value) -> KisResult<String> {
+    match val["HASH"].as_str() {
+        Some(hash) => Ok(hash.trim_matches('"').to_string()),
+        None => Err(Error::Auth(format!("no HASH in hashkey response: {val}"))),
+    }
+}
+
+/// 접근토큰발급(P)
+pub(crate) fn access_token() -> Endpoint {
+    Endpoint::new("/oauth2/tokenP", RequestType::POSTTOKEN)
+}
+
+/// 접근토큰폐기(P)
+pub(crate) fn revoke_access_token(token: &str) -> Endpoint {
+    Endpoint::new("/oauth2/revokeP", RequestType::POSTREVOKE).param("token", token)
+}
+
+/// 접근토큰폐기 응답 확인
+pub(crate) fn check_revoke_response(val: &Value) -> KisResult<()> {
+    if val["code"].as_u64() != Some(200) {
+        return Err(Error::Auth(format!("revoke failed: {val}")));
+    }
+    Ok(())
+}
+
+/// 실시간 (웹소켓) 접속키 발급
+pub(crate) fn approval_key() -> Endpoint {
+    Endpoint::new("/oauth2/Approval", RequestType::POSTAPPROVAL)
+}
+
+// 국내주식주문
+
+/// 주식잔고조회
+pub(crate) fn account_balance(conf: &AccountConfig) -> Endpoint {
+    Endpoint::get(
+        "/uapi/domestic-stock/v1/trading/inquire-balance",
+        tr_id(conf, "TTTC8434R", "VTTC8434R"),
+    )
+    .account(conf)
+    .param("AFHR_FLPR_YN", "N")
+    .param("FNCG_AMT_AUTO_RDPT_YN", "N")
+    .param("FUND_STTL_ICLD_YN", "N")
+    .param("INQR_DVSN", "01")
+    .param("OFL_YN", "N")
+    .param("PRCS_DVSN", "01")
+    .param("UNPR_DVSN", "01")
+    .param("CTX_AREA_FK100", "")
+    .param("CTX_AREA_NK100", "")
+}
+
+/// 매수가능조회
+pub(crate) fn buying_power(
+    conf: &AccountConfig,
+    ticker: &str,
+    order_type: OrderType,
+    price: u32,
+) -> Endpoint {
+    Endpoint::get(
+        "/uapi/domestic-stock/v1/trading/inquire-psbl-order",
+        tr_id(conf, "TTTC8908R", "VTTC8908R"),
+    )
+    .account(conf)
+    .param("PDNO", ticker)
+    .param("ORD_UNPR", &price.to_string())
+    .param("ORD_DVSN", order_type.code())
+    .param("CMA_EVLU_AMT_ICLD_YN", "N")
+    .param("OVRS_ICLD_YN", "N")
+}
+
+/// 신용매수가능조회 (실전투자 전용)
+pub(crate) fn credit_buying_power(
+    conf: &AccountConfig,
+    ticker: &str,
+    order_type: OrderType,
+    price: u32,
+    credit_type: CreditType,
+) -> KisResult<Endpoint> {
+    real_only(conf)?;
+
+    Ok(Endpoint::get(
+        "/uapi/domestic-stock/v1/trading/inquire-credit-psamount",
+        "TTTC8909R",
+    )
+    .account(conf)
+    .param("PDNO", ticker)
+    .param("ORD_UNPR", &price.to_string())
+    .param("ORD_DVSN", order_type.code())
+    .param("CRDT_TYPE", credit_type.code())
+    .param("CMA_EVLU_AMT_ICLD_YN", "N")
+    .param("OVRS_ICLD_YN", "N"))
+}
+
+/// 주식주문, `order` 의 자금 구분에 따라 현금 또는 신용 주문
+pub(crate) fn order_stock(conf: &AccountConfig, order: &OrderRequest) -> KisResult<Endpoint> {
+    match order.get_funding() {
+        Funding::Cash => Ok(order_cash(conf, order)),
+        Funding::Credit {
+            credit_type,
+            loan_date,
+        } => order_credit(conf, order, *credit_type, loan_date),
+    }
+}
+
+/// 주식주문(현금)
+fn order_cash(conf: &AccountConfig, order: &OrderRequest) -> Endpoint {
+    let tr_id = match order.get_side() {
+        OrderSide::Buy => tr_id(conf, "TTTC0802U", "VTTC0802U"),
+        OrderSide::Sell => tr_id(conf, "TTTC0801U", "VTTC0801U"),
+    };
+
+    Endpoint::order("/uapi/domestic-stock/v1/trading/order-cash", tr_id)
+        .account(conf)
+        .param("PDNO", order.get_ticker())
+        .param("ORD_DVSN", order.get_order_type().code())
+        .param("ORD_QTY", &order.get_quantity().to_string())
+        .param("ORD_UNPR", &order.get_price().to_string())
+        // .param("CTAC_TLNO", "")
+        // .param("SLL_TYPE", "01")
+        .param("ALGO_NO", "")
+}
+
+/// 주식주문(신용) (실전투자 전용)
+fn order_credit(
+    conf: &AccountConfig,
+    order: &OrderRequest,
+    credit_type: CreditType,
+    loan_date: &str,
+) -> KisResult<Endpoint> {
+    real_only(conf)?;
+    let tr_id = match order.get_side() {
+        OrderSide::Buy => "TTTC0852U",
+        OrderSide::Sell => "TTTC0851U",
+    };
+    let loan_date = match loan_date {
+        "" => kst_today(),
+        date => date.to_string(),
+    };
+
+    Ok(
+        Endpoint::order("/uapi/domestic-stock/v1/trading/order-credit", tr_id)
+            .account(conf)
+            .param("PDNO", order.get_ticker())
+            .param("CRDT_TYPE", credit_type.code())
+            .param("LOAN_DT", &loan_date)
+            .param("ORD_DVSN", order.get_order_type().code())
+            .param("ORD_QTY", &order.get_quantity().to_string())
+            .param("ORD_UNPR", &order.get_price().to_string()),
+    )
+}
+
+/// 주식주문(정정취소) : rvse_cncl_dvsn_cd 01 정정, 02 취소
+pub(crate) fn order_revise(
+    conf: &AccountConfig,
+    order: &OrderAck,
+    rvse_cncl_dvsn_cd: &str,
+    order_type: OrderType,
+    quantity: OrderQuantity,
+    price: u32,
+) -> KisResult<Endpoint> {
+    let (qty, qty_all) = match quantity {
+        OrderQuantity::Partial(0) => {
+            return Err(Error::InvalidRequest(
+                "order quantity must be greater than 0".to_string(),
+            ))
+        }
+        OrderQuantity::Partial(n) => (n, "N"),
+        OrderQuantity::AllRemaining => (0, "Y"),
+    };
+
+    Ok(Endpoint::order(
+        "/uapi/domestic-stock/v1/trading/order-rvsecncl",
+        tr_id(conf, "TTTC0803U", "VTTC0803U"),
+    )
+    .account(conf)
+    .param("KRX_FWDG_ORD_ORGNO", &order.krx_orgno)
+    .param("ORGN_ODNO", &order.order_no)
+    .param("ORD_DVSN", order_type.code())
+    .param("RVSE_CNCL_DVSN_CD", rvse_cncl_dvsn_cd)
+    .param("ORD_QTY", &qty.to_string())
+    .param("ORD_UNPR", &price.to_string())
+    .param("QTY_ALL_ORD_YN", qty_all))
+}
+
+/// 주식예약주문 (실전투자 전용)
+pub(crate) fn order_reservation(
+    conf: &AccountConfig,
+    order: &ReservationOrder,
+) -> KisResult<Endpoint> {
+    real_only(conf)?;
+    order.validate()?;
+
+    Ok(reservation_order(
+        Endpoint::order("/uapi/domestic-stock/v1/trading/order-resv", "CTSC0008U"),
+        conf,
+        order,
+    )
+    .param("LDNG_DT", ""))
+}
+
+/// 주식예약주문정정 (실전투자 전용), 예약 내용을 `order` 로 변경
+pub(crate) fn revise_reservation(
+    conf: &AccountConfig,
+    reservation: &Reservation,
+    order: &ReservationOrder,
+) -> KisResult<Endpoint> {
+    real_only(conf)?;
+    order.validate()?;
+
+    Ok(reservation_target(
+        reservation_order(
+            Endpoint::order(
+                "/uapi/domestic-stock/v1/trading/order-resv-rvsecncl",
+                "CTSC0013U",
+            ),
+            conf,
+            order,
+        ),
+        reservation,
+    ))
+}
+
+/// 주식예약주문취소 (실전투자 전용)
+pub(crate) fn cancel_reservation(
+    conf: &AccountConfig,
+    reservation: &Reservation,
+) -> KisResult<Endpoint> {
+    real_only(conf)?;
+    // 취소 대상은 예약주문순번으로 식별, 나머지는 예약 내용 그대로
+    let order_type = reservation.order_type.ok_or_else(|| {
+        Error::InvalidRequest(format!(
+            "unknown order type of reservation {}",
+            reservation.seq
+        ))
+    })?;
+
+    Ok(reservation_target(
+        Endpoint::order(
+            "/uapi/domestic-stock/v1/trading/order-resv-rvsecncl",
+            "CTSC0009U",
+        )
+        .account(conf)
+        .param("PDNO", &reservation.ticker)
+        .param("ORD_QTY", &reservation.quantity.to_string())
+        .param("ORD_UNPR", &reservation.price.to_string())
+        .param("SLL_BUY_DVSN_CD", reservation.side.code())
+        .param("ORD_DVSN_CD", order_type.code())
+        .param("ORD_OBJT_CBLC_DVSN_CD", "10")
+        .param("LOAN_DT", "")
+        .param("RSVN_ORD_END_DT", &reservation.end_date),
+        reservation,
+    ))
+}
+
+/// 주식예약주문조회 (실전투자 전용), 예약주문일자 기준
+pub(crate) fn reservations(
+    conf: &AccountConfig,
+    start_date: &str,
+    end_date: &str,
+) -> KisResult<Endpoint> {
+    real_only(conf)?;
+    validate_period(start_date, end_date, "")?;
+
+    Ok(Endpoint::get(
+        "/uapi/domestic-stock/v1/trading/order-resv-ccnl",
+        "CTSC0004R",
+    )
+    .account(conf)
+    .param("RSVN_ORD_ORD_DT", start_date)
+    .param("RSVN_ORD_END_DT", end_date)
+    .param("RSVN_ORD_SEQ", "")
+    .param("TMNL_MDIA_KIND_CD", "00")
+    .param("PRCS_DVSN_CD", "0")
+    .param("CNCL_YN", "Y")
+    .param("PDNO", "")
+    .param("SLL_BUY_DVSN_CD", "")
+    .param("CTX_AREA_FK200", "")
+    .param("CTX_AREA_NK200", ""))
+}
+
+/// 예약주문 내용, 현금 주문 (ORD_OBJT_CBLC_DVSN_CD 10) 만 가능 (`ReservationOrder::validate`)
+fn reservation_order(ep: Endpoint, conf: &AccountConfig, order: &ReservationOrder) -> Endpoint {
+    let req = order.get_order();
+    ep.account(conf)
+        .param("PDNO", req.get_ticker())
+        .param("ORD_QTY", &req.get_quantity().to_string())
+        .param("ORD_UNPR", &req.get_price().to_string())
+        .param("SLL_BUY_DVSN_CD", req.get_side().code())
+        .param("ORD_DVSN_CD", req.get_order_type().code())
+        .param("ORD_OBJT_CBLC_DVSN_CD", "10")
+        .param("LOAN_DT", "")
+        .param("RSVN_ORD_END_DT", order.get_end_date())
+}
+
+/// 정정/취소할 예약주문
+fn reservation_target(ep: Endpoint, reservation: &Reservation) -> Endpoint {
+    ep.param("CTAL_TLNO", "")
+        .param("RSVN_ORD_SEQ", &reservation.seq.to_string())
+        .param("RSVN_ORD_ORGNO", "")
+        .param("RSVN_ORD_ORD_DT", &reservation.order_date)
+}
+
+/// 주식일별주문체결조회 (3개월 이내)
+pub(crate) fn daily_executions(
+    conf: &AccountConfig,
+    query: &ExecutionQuery,
+) -> KisResult<Endpoint> {
+    query.validate()?;
+    let side = query.get_side().map(|side| side.code()).unwrap_or("00");
+    // 3개월 이내와 이전은 tr_id 가 다름
+    let executions_tr_id = if query.is_before_3_months()? {
+        tr_id(conf, "CTSC9115R", "VTSC9115R")
+    } else {
+        tr_id(conf, "TTTC8001R", "VTTC8001R")
+    };
+
+    Ok(Endpoint::get(
+        "/uapi/domestic-stock/v1/trading/inquire-daily-ccld",
+        executions_tr_id,
+    )
+    .account(conf)
+    .param("INQR_STRT_DT", query.get_start_date())
+    .param("INQR_END_DT", query.get_end_date())
+    .param("SLL_BUY_DVSN_CD", side)
+    .param("INQR_DVSN", "00")
+    .param("PDNO", query.get_ticker())
+    .param("CCLD_DVSN", query.get_fill().code())
+    .param("ORD_GNO_BRNO", "")
+    .param("ODNO", "")
+    .param("INQR_DVSN_3", "00")
+    .param("INQR_DVSN_1", "")
+    .param("CTX_AREA_FK100", "")
+    .param("CTX_AREA_NK100", ""))
+}
+
+/// 주식정정취소가능주문조회 (실전투자 전용)
+pub(crate) fn ordered_list(conf: &AccountConfig) -> KisResult<Endpoint> {
+    real_only(conf)?;
+
+    Ok(Endpoint::get(
+        "/uapi/domestic-stock/v1/trading/inquire-psbl-rvsecncl",
+        "TTTC8036R",
+    )
+    .account(conf)
+    .param("CTX_AREA_FK100", "")
+    .param("CTX_AREA_NK100", "")
+    .param("INQR_DVSN_1", "0")
+    .param("INQR_DVSN_2", "0"))
+}
+
+/// 기간별매매손익현황조회 (실전투자 전용), 매매일자별 종목 단위
+pub(crate) fn trade_profits(conf: &AccountConfig, query: &ProfitQuery) -> KisResult<Endpoint> {
+    Ok(period_profit(
+        conf,
+        query,
+        "/uapi/domestic-stock/v1/trading/inquire-period-trade-profit",
+        "TTTC8715R",
+    )?
+    .param("SORT_DVSN", "01")
+    .param("CBLC_DVSN", "00"))
+}
+
+/// 기간별손익일별합산조회 (실전투자 전용), 일자 단위
+pub(crate) fn daily_profits(conf: &AccountConfig, query: &ProfitQuery) -> KisResult<Endpoint> {
+    Ok(period_profit(
+        conf,
+        query,
+        "/uapi/domestic-stock/v1/trading/inquire-period-profit",
+        "TTTC8708R",
+    )?
+    .param("SORT_DVSN", "01")
+    .param("INQR_DVSN", "00")
+    .param("CBLC_DVSN", "00"))
+}
+
+fn period_profit(
+    conf: &AccountConfig,
+    query: &ProfitQuery,
+    url: &'static str,
+    tr_id: &'static str,
+) -> KisResult<Endpoint> {
+    real_only(conf)?;
+    query.validate()?;
+
+    Ok(Endpoint::get(url, tr_id)
+        .account(conf)
+        .param("PDNO", query.get_ticker())
+        .param("INQR_STRT_DT", query.get_start_date())
+        .param("INQR_END_DT", query.get_end_date())
+        .param("CTX_AREA_FK100", "")
+        .param("CTX_AREA_NK100", ""))
+}
+
+// 국내주식시세
+
+/// 주식현재가 시세[v1_국내주식-008]
+pub(crate) fn stock_current_price(ticker: &str) -> Endpoint {
+    stock_quotation(
+        "/uapi/domestic-stock/v1/quotations/inquire-price",
+        "FHKST01010100",
+        ticker,
+    )
+}
+
+/// 주식현재가 체결[v1_국내주식-009]
+pub(crate) fn stock_current_concluded(ticker: &str) -> Endpoint {
+    stock_quotation(
+        "/uapi/domestic-stock/v1/quotations/inquire-ccnl",
+        "FHKST01010300",
+        ticker,
+    )
+}
+
+/// 주식현재가 일자별[v1_국내주식-010]
+pub(crate) fn stock_daily_price(ticker: &str) -> Endpoint {
+    stock_quotation(
+        "/uapi/domestic-stock/v1/quotations/inquire-daily-price",
+        "FHKST01010400",
+        ticker,
+    )
+    .param("fid_org_adj_prc", "1")
+    .param("fid_period_div_code", "D") // D,W,M
+}
+
+/// 주식현재가 호가 예상체결[v1_국내주식-011]
+pub(crate) fn stock_bid_ask_prices(ticker: &str) -> Endpoint {
+    stock_quotation(
+        "/uapi/domestic-stock/v1/quotations/inquire-asking-price-exp-ccn",
+        "FHKST01010200",
+        ticker,
+    )
+}
+
+/// 주식현재가 투자자[v1_국내주식-012]
+pub(crate) fn stock_investor_list(ticker: &str) -> Endpoint {
+    stock_quotation(
+        "/uapi/domestic-stock/v1/quotations/inquire-investor",
+        "FHKST01010900",
+        ticker,
+    )
+}
+
+/// 주식현재가 회원사[v1_국내주식-013]
+pub(crate) fn stock_membership_list(ticker: &str) -> Endpoint {
+    stock_quotation(
+        "/uapi/domestic-stock/v1/quotations/inquire-member",
+        "FHKST01010600",
+        ticker,
+    )
+}
+
+/// ELW현재가 시세[v1_국내주식-014]
+pub(crate) fn stock_elw_price(ticker: &str) -> Endpoint {
+    Endpoint::get(
+        "/uapi/domestic-stock/v1/quotations/inquire-elw-price",
+        "FHKEW15010000",
+    )
+    .param("fid_cond_mrkt_div_code", "W")
+    .param("fid_input_iscd", ticker)
+}
+
+/// 국내주식기간별시세(일/주/월/년)[v1_국내주식-016]
+pub(crate) fn stock_duration_prices(
+    ticker: &str,
+    begin: &str,
+    end: &str,
+    duration: &str,
+) -> Endpoint {
+    Endpoint::get(
+        "/uapi/domestic-stock/v1/quotations/inquire-daily-itemchartprice",
+        "[실전투자]",
+    )
+    .param("fid_cond_mrkt_div_code", "J")
+    .param("fid_input_iscd", ticker)
+    .param("fid_input_date_1", begin)
+    .param("fid_input_date_2", end)
+    .param("fid_period_div_code", duration)
+    .param("FID_ORG_ADJ_PRC", "0") // 0:수정주가 1:원주가
+}
+
+/// 국내주식업종기간별시세(일/주/월/년)[v1_국내주식-021]
+pub(crate) fn sector_duration_prices(
+    section: &str,
+    begin: &str,
+    end: &str,
+    duration: &str,
+) -> Endpoint {
+    Endpoint::get(
+        "/uapi/domestic-stock/v1/quotations/inquire-daily-indexchartprice",
+        "[실전투자]",
+    )
+    .param("fid_cond_mrkt_div_code", "U")
+    .param("fid_input_iscd", section)
+    .param("fid_input_date_1", begin)
+    .param("fid_input_date_2", end)
+    .param("fid_period_div_code", duration)
+}
+
+fn stock_quotation(url: &'static str, tr_id: &'static str, ticker: &str) -> Endpoint {
+    Endpoint::get(url, tr_id)
+        .param("fid_cond_mrkt_div_code", "J")
+        .param("fid_input_iscd", ticker)
+}
+
+/// 한국 시간 기준 오늘 날짜 (YYYYMMDD)
+pub(crate) fn kst_today() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+        + 9 * 60 * 60;
+    let (y, m, d) = civil_from_days((secs / 86400) as i64);
+    format!("{y:04}{m:02}{d:02}")
+}
+
+/// 1970-01-01 부터의 일수를 (년, 월, 일) 로 변환
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    (y, m, d)
+}
+
+fn parse_header_name(name: &str) -> KisResult<HeaderName> {
+    name.parse::<HeaderName>()
+        .map_err(|_| Error::Config(format!("invalid header name: {name}")))
+}
+
+fn parse_header_value(value: &str) -> KisResult<HeaderValue> {
+    value
+        .parse::<HeaderValue>()
+        .map_err(|_| Error::Config(format!("invalid header value: {value}")))
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+
+    #[test]
+    fn test_order_cash_request() {
+        let conf = AccountConfig::mock("http://localhost", false);
+        let order = OrderRequest::buy("005930")
+            .order_type(OrderType::Limit)
+            .quantity(3)
+            .price(70000)
+            .build()
+            .unwrap();
+
+        let ep = order_cash(&conf, &order);
+        assert!(ep.hashkey);
+
+        let req = ep.to_request(&conf, Some("token"), Some("hash")).unwrap();
+        assert_eq!(
+            req.url,
+            "http://localhost/uapi/domestic-stock/v1/trading/order-cash"
+        );
+        assert_eq!(req.headers["tr_id"], "VTTC0802U");
+        assert_eq!(req.headers["hashkey"], "hash");
+        assert_eq!(req.headers["authorization"], "Bearer token");
+        assert_eq!(req.parameters["CANO"], "50067252");
+        assert_eq!(req.parameters["ORD_DVSN"], "00");
+        assert_eq!(req.parameters["ORD_QTY"], "3");
+    }
+
+    #[test]
+    fn test_order_credit_request() {
+        let conf = AccountConfig::mock("http://localhost", true);
+        let order = OrderRequest::buy("005930")
+            .quantity(3)
+            .price(70000)
+            .credit(CreditType::OwnLoanNew, "")
+            .build()
+            .unwrap();
+
+        let ep = order_stock(&conf, &order).unwrap();
+        assert!(ep.hashkey);
+        let req = ep.to_request(&conf, None, None).unwrap();
+        assert_eq!(
+            req.url,
+            "http://localhost/uapi/domestic-stock/v1/trading/order-credit"
+        );
+        assert_eq!(req.headers["tr_id"], "TTTC0852U");
+        assert_eq!(req.parameters["CRDT_TYPE"], "21");
+        assert_eq!(req.parameters["LOAN_DT"], kst_today());
+
+        // 현금 주문은 order-cash 로
+        let cash = OrderRequest::buy("005930")
+            .quantity(3)
+            .price(70000)
+            .build()
+            .unwrap();
+        let req = order_stock(&conf, &cash)
+            .unwrap()
+            .to_request(&conf, None, None)
+            .unwrap();
+        assert_eq!(
+            req.url,
+            "http://localhost/uapi/domestic-stock/v1/trading/order-cash"
+        );
+
+        let virt = AccountConfig::mock("http://localhost", false);
+        assert!(matches!(order_stock(&virt, &order), Err(Error::Config(_))));
+    }
+
+    #[test]
+    fn test_next_page() {
+        let conf = AccountConfig::mock("http://localhost", false);
+        let ep = account_balance(&conf);
+        let mut res = KisResponse {
+            body: serde_json::json!({"ctx_area_fk100": "FK", "ctx_area_nk100": "NK"}),
+            tr_cont: "M".to_string(),
+        };
+
+        let next = ep.next_page(&res).unwrap();
+        let req = next.to_request(&conf, None, None).unwrap();
+        assert_eq!(req.headers["tr_cont"], "N");
+        assert_eq!(req.parameters["CTX_AREA_FK100"], "FK");
+        assert_eq!(req.parameters["CTX_AREA_NK100"], "NK");
+        // 같은 키가 다시 오면 중단
+        assert!(next.next_page(&res).is_none());
+
+        res.tr_cont = "D".to_string();
+        assert!(ep.next_page(&res).is_none());
+        res.tr_cont = "F".to_string();
+        assert!(stock_current_price("005930").next_page(&res).is_none());
+    }
+
+    #[test]
+    fn test_civil_from_days() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(19723), (2024, 1, 1));
+        assert_eq!(civil_from_days(19782), (2024, 2, 29));
+        assert_eq!(kst_today().len(), 8);
+    }
+
+    #[test]
+    fn test_oauth_request_without_token() {
+        let conf = AccountConfig::mock("http://localhost", true);
+        let req = access_token().to_request(&conf, None, None).unwrap();
+        assert!(!req.headers.contains_key("authorization"));
+        assert_eq!(req.parameters["grant_type"], "client_credentials");
+
+        assert!(ordered_list(&conf).is_ok());
+        let conf = AccountConfig::mock("http://localhost", false);
+        assert!(matches!(ordered_list(&conf), Err(Error::Config(_))));
+    }
+
+    #[test]
+    fn test_cancel_reservation_unknown_order_type() {
+        let conf = AccountConfig::mock("http://localhost", true);
+        let mut reservation: Reservation = serde_json::from_str(
+            r#"{"rsvn_ord_seq":"27","rsvn_ord_ord_dt":"20240105","pdno":"005930","sll_buy_dvsn_cd":"02","ord_dvsn_cd":"99","ord_rsvn_qty":"10","ord_rsvn_unpr":"70000"}"#,
+        )
+        .unwrap();
+        assert!(reservation.order_type.is_none());
+        assert!(matches!(
+            cancel_reservation(&conf, &reservation),
+            Err(Error::InvalidRequest(_))
+        ));
+
+        reservation.order_type = Some(OrderType::Limit);
+        let req = cancel_reservation(&conf, &reservation)
+            .unwrap()
+            .to_request(&conf, None, None)
+            .unwrap();
+        assert_eq!(req.parameters["ORD_DVSN_CD"], "00");
+        assert_eq!(req.parameters["RSVN_ORD_SEQ"], "27");
+    }
+}
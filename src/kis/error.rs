@@ -0,0 +1,73 @@
+use reqwest::StatusCode;
+use thiserror::Error as ThisError;
+
+pub type KisResult<T> = Result<T, Error>;
+
+/// KIS API 호출 중 발생하는 에러
+#[derive(Debug, ThisError)]
+pub enum Error {
+    /// 연결 실패, 타임아웃 등 전송 계층 에러
+    #[error("transport error: {0}")]
+    Transport(#[from] reqwest::Error),
+
+    /// HTTP status 200 이외의 응답
+    #[error("HTTP {status}: {body}")]
+    Http { status: StatusCode, body: String },
+
+    /// KIS 업무 에러 (rt_cd != "0")
+    #[error("KIS error [{msg_cd}] {msg1} (rt_cd: {rt_cd})")]
+    Api {
+        rt_cd: String,
+        msg_cd: String,
+        msg1: String,
+    },
+
+    /// access token, hashkey 발급 등 인증 관련 에러
+    #[error("auth error: {0}")]
+    Auth(String),
+
+    /// 계좌 설정 파일 관련 에러
+    #[error("config error: {0}")]
+    Config(String),
+
+    /// 주문 수량, 단가 등 요청 값 검증 에러
+    #[error("invalid request: {0}")]
+    InvalidRequest(String),
+
+    /// 실시간 (웹소켓) 연결 에러
+    #[error("websocket error: {0}")]
+    WebSocket(Box<tungstenite::Error>),
+
+    /// 세션당 실시간 등록 한도 초과
+    #[error("realtime subscription limit ({limit}) reached")]
+    SubscriptionLimit { limit: usize },
+
+    /// 실시간 연결이 끊겼거나 응답이 없음
+    #[error("realtime error: {0}")]
+    Realtime(String),
+
+    /// 응답 데이터 파싱 에러
+    #[error("parse error: {0}")]
+    Parse(String),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Parse(e.to_string())
+    }
+}
+
+impl From<csv::Error> for Error {
+    fn from(e: csv::Error) -> Self {
+        Error::Parse(e.to_string())
+    }
+}
+
+impl From<tungstenite::Error> for Error {
+    fn from(e: tungstenite::Error) -> Self {
+        Error::WebSocket(Box::new(e))
+    }
+}
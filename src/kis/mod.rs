@@ -1,7 +1,37 @@
 pub mod account;
 pub mod api;
+#[cfg(feature = "async")]
+pub mod async_api;
+mod client;
+mod de;
+mod endpoint;
+pub mod error;
+#[cfg(test)]
+mod mock_server;
+pub mod order;
+pub mod quotation;
+pub mod rate_limit;
+pub mod realtime;
 pub mod request;
+pub mod response;
+pub mod retry;
+pub mod token;
+pub mod trading;
 pub mod ws;
 
 pub use account::*;
+pub use error::{Error, KisResult};
+pub use order::{
+    CreditType, Funding, OrderAck, OrderQuantity, OrderRequest, OrderSide, OrderType,
+    ReservationAck, ReservationOrder,
+};
+pub use rate_limit::{Priority, RateLimiter};
+pub use realtime::{ExecutionNotice, RealtimeOrderBook, Tick};
 pub use request::*;
+pub use retry::RetryPolicy;
+pub use token::AccessToken;
+pub use trading::{
+    AccountBalance, AccountSummary, BuyingPower, DailyProfit, Execution, ExecutionQuery,
+    FillFilter, Holding, ProfitQuery, ProfitReport, ProfitSummary, Reservation, TradeProfit,
+};
+pub use ws::{ConnectionEvent, RealtimeClient};
@@ -0,0 +1,595 @@
+//! 국내주식주문 요청 타입
+
+use serde::Deserialize;
+
+use super::de;
+use super::trading::validate_date;
+use super::{Error, KisResult};
+
+/// 주문구분 (ORD_DVSN)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderType {
+    /// 00 : 지정가
+    Limit,
+    /// 01 : 시장가
+    Market,
+    /// 02 : 조건부지정가
+    ConditionalLimit,
+    /// 03 : 최유리지정가
+    Best,
+    /// 04 : 최우선지정가
+    First,
+    /// 05 : 장전 시간외 (전일 종가)
+    PreMarket,
+    /// 06 : 장후 시간외 (당일 종가)
+    AfterHours,
+    /// 07 : 시간외 단일가
+    AfterHoursSinglePrice,
+    /// 11 : IOC지정가
+    IocLimit,
+    /// 12 : FOK지정가
+    FokLimit,
+    /// 13 : IOC시장가
+    IocMarket,
+    /// 14 : FOK시장가
+    FokMarket,
+    /// 15 : IOC최유리
+    IocBest,
+    /// 16 : FOK최유리
+    FokBest,
+}
+
+impl OrderType {
+    /// KIS 주문구분 코드
+    pub fn code(&self) -> &'static str {
+        match self {
+            OrderType::Limit => "00",
+            OrderType::Market => "01",
+            OrderType::ConditionalLimit => "02",
+            OrderType::Best => "03",
+            OrderType::First => "04",
+            OrderType::PreMarket => "05",
+            OrderType::AfterHours => "06",
+            OrderType::AfterHoursSinglePrice => "07",
+            OrderType::IocLimit => "11",
+            OrderType::FokLimit => "12",
+            OrderType::IocMarket => "13",
+            OrderType::FokMarket => "14",
+            OrderType::IocBest => "15",
+            OrderType::FokBest => "16",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        let order_type = match code {
+            "00" => OrderType::Limit,
+            "01" => OrderType::Market,
+            "02" => OrderType::ConditionalLimit,
+            "03" => OrderType::Best,
+            "04" => OrderType::First,
+            "05" => OrderType::PreMarket,
+            "06" => OrderType::AfterHours,
+            "07" => OrderType::AfterHoursSinglePrice,
+            "11" => OrderType::IocLimit,
+            "12" => OrderType::FokLimit,
+            "13" => OrderType::IocMarket,
+            "14" => OrderType::FokMarket,
+            "15" => OrderType::IocBest,
+            "16" => OrderType::FokBest,
+            _ => return None,
+        };
+        Some(order_type)
+    }
+
+    /// 주문단가 지정이 필요한 주문인지 여부, 나머지는 단가를 0 으로 보내야 함
+    pub fn requires_price(&self) -> bool {
+        matches!(
+            self,
+            OrderType::Limit
+                | OrderType::ConditionalLimit
+                | OrderType::AfterHoursSinglePrice
+                | OrderType::IocLimit
+                | OrderType::FokLimit
+        )
+    }
+}
+
+/// 매수/매도 구분
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderSide {
+    Buy,
+    Sell,
+}
+
+impl OrderSide {
+    /// KIS 매도매수구분코드 (01 매도, 02 매수)
+    pub fn code(&self) -> &'static str {
+        match self {
+            OrderSide::Sell => "01",
+            OrderSide::Buy => "02",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "01" => Some(OrderSide::Sell),
+            "02" => Some(OrderSide::Buy),
+            _ => None,
+        }
+    }
+}
+
+/// 신용거래구분 (CRDT_TYPE), 융자는 매수 신규/매도 상환, 대주는 매도 신규/매수 상환
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CreditType {
+    /// 21 : 자기융자신규
+    OwnLoanNew,
+    /// 23 : 유통융자신규
+    FinanceLoanNew,
+    /// 25 : 자기융자상환
+    OwnLoanRepay,
+    /// 27 : 유통융자상환
+    FinanceLoanRepay,
+    /// 24 : 자기대주신규
+    OwnStockLoanNew,
+    /// 22 : 유통대주신규
+    FinanceStockLoanNew,
+    /// 28 : 자기대주상환
+    OwnStockLoanRepay,
+    /// 26 : 유통대주상환
+    FinanceStockLoanRepay,
+}
+
+impl CreditType {
+    /// KIS 신용거래구분 코드
+    pub fn code(&self) -> &'static str {
+        match self {
+            CreditType::OwnLoanNew => "21",
+            CreditType::FinanceStockLoanNew => "22",
+            CreditType::FinanceLoanNew => "23",
+            CreditType::OwnStockLoanNew => "24",
+            CreditType::OwnLoanRepay => "25",
+            CreditType::FinanceStockLoanRepay => "26",
+            CreditType::FinanceLoanRepay => "27",
+            CreditType::OwnStockLoanRepay => "28",
+        }
+    }
+
+    /// 이 신용거래구분으로 주문할 수 있는 매수/매도 구분
+    pub fn side(&self) -> OrderSide {
+        match self {
+            CreditType::OwnLoanNew
+            | CreditType::FinanceLoanNew
+            | CreditType::OwnStockLoanRepay
+            | CreditType::FinanceStockLoanRepay => OrderSide::Buy,
+            _ => OrderSide::Sell,
+        }
+    }
+
+    /// 신규 (대출일자는 주문일자)
+    pub fn is_new(&self) -> bool {
+        matches!(
+            self,
+            CreditType::OwnLoanNew
+                | CreditType::FinanceLoanNew
+                | CreditType::OwnStockLoanNew
+                | CreditType::FinanceStockLoanNew
+        )
+    }
+}
+
+/// 주문 자금 구분
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Funding {
+    /// 현금 주문 (order-cash)
+    #[default]
+    Cash,
+    /// 신용 주문 (order-credit), `loan_date` 는 YYYYMMDD, 신규는 빈 문자열이면 주문일자
+    Credit {
+        credit_type: CreditType,
+        loan_date: String,
+    },
+}
+
+/// 정정/취소 수량
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderQuantity {
+    /// 일부 수량 정정/취소
+    Partial(u32),
+    /// 잔량 전부 정정/취소
+    AllRemaining,
+}
+
+/// 검증된 주식 주문 요청
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderRequest {
+    ticker: String,
+    side: OrderSide,
+    order_type: OrderType,
+    quantity: u32,
+    price: u32,
+    funding: Funding,
+}
+
+impl OrderRequest {
+    /// 매수 주문 builder
+    pub fn buy(ticker: &str) -> OrderRequestBuilder {
+        OrderRequestBuilder::new(ticker, OrderSide::Buy)
+    }
+
+    /// 매도 주문 builder
+    pub fn sell(ticker: &str) -> OrderRequestBuilder {
+        OrderRequestBuilder::new(ticker, OrderSide::Sell)
+    }
+
+    pub fn get_ticker(&self) -> &str {
+        &self.ticker
+    }
+
+    pub fn get_side(&self) -> OrderSide {
+        self.side
+    }
+
+    pub fn get_order_type(&self) -> OrderType {
+        self.order_type
+    }
+
+    pub fn get_quantity(&self) -> u32 {
+        self.quantity
+    }
+
+    pub fn get_price(&self) -> u32 {
+        self.price
+    }
+
+    pub fn get_funding(&self) -> &Funding {
+        &self.funding
+    }
+}
+
+/// 주문 접수 결과, 정정/취소 및 체결 조회에 사용
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct OrderAck {
+    /// 한국거래소전송주문조직번호
+    #[serde(rename = "KRX_FWDG_ORD_ORGNO")]
+    pub krx_orgno: String,
+    /// 주문번호
+    #[serde(rename = "ODNO")]
+    pub order_no: String,
+    /// 주문시각 (HHMMSS)
+    #[serde(rename = "ORD_TMD", default)]
+    pub order_time: String,
+}
+
+/// 예약주문 요청, 다음 영업일 장 시작 전에 일괄 전송된다
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReservationOrder {
+    order: OrderRequest,
+    end_date: String,
+}
+
+impl ReservationOrder {
+    /// 지정가, 시장가, 조건부지정가, 장전 시간외 주문만 예약 가능
+    pub fn new(order: OrderRequest) -> Self {
+        Self {
+            order,
+            end_date: String::new(),
+        }
+    }
+
+    /// 기간예약 종료일 (YYYYMMDD), 기본값은 다음 영업일 하루
+    pub fn end_date(mut self, end_date: &str) -> Self {
+        self.end_date = end_date.to_string();
+        self
+    }
+
+    pub fn get_order(&self) -> &OrderRequest {
+        &self.order
+    }
+
+    pub fn get_end_date(&self) -> &str {
+        &self.end_date
+    }
+
+    /// 예약 가능한 주문구분과 종료일 형식 검증, 신용 예약주문은 지원하지 않음
+    pub(crate) fn validate(&self) -> KisResult<()> {
+        if let Funding::Credit { .. } = self.order.get_funding() {
+            return Err(Error::InvalidRequest(
+                "credit order can not be reserved".to_string(),
+            ));
+        }
+        let order_type = self.order.get_order_type();
+        if !matches!(
+            order_type,
+            OrderType::Limit
+                | OrderType::Market
+                | OrderType::ConditionalLimit
+                | OrderType::PreMarket
+        ) {
+            return Err(Error::InvalidRequest(format!(
+                "{order_type:?} order can not be reserved"
+            )));
+        }
+        if !self.end_date.is_empty() {
+            validate_date(&self.end_date)?;
+        }
+        Ok(())
+    }
+}
+
+/// 예약주문 접수 결과
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ReservationAck {
+    /// 예약주문순번
+    #[serde(rename = "RSVN_ORD_SEQ", deserialize_with = "de::number")]
+    pub seq: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct OrderRequestBuilder {
+    ticker: String,
+    side: OrderSide,
+    order_type: OrderType,
+    quantity: u32,
+    price: u32,
+    funding: Funding,
+}
+
+impl OrderRequestBuilder {
+    fn new(ticker: &str, side: OrderSide) -> Self {
+        Self {
+            ticker: ticker.to_string(),
+            side,
+            order_type: OrderType::Limit,
+            quantity: 0,
+            price: 0,
+            funding: Funding::Cash,
+        }
+    }
+
+    /// 주문구분, 기본값은 지정가
+    pub fn order_type(mut self, order_type: OrderType) -> Self {
+        self.order_type = order_type;
+        self
+    }
+
+    pub fn quantity(mut self, quantity: u32) -> Self {
+        self.quantity = quantity;
+        self
+    }
+
+    pub fn price(mut self, price: u32) -> Self {
+        self.price = price;
+        self
+    }
+
+    /// 신용 주문, 기본값은 현금 주문
+    ///
+    /// `loan_date` : 상환은 대출일자 (YYYYMMDD), 신규는 빈 문자열이면 주문일자
+    pub fn credit(mut self, credit_type: CreditType, loan_date: &str) -> Self {
+        self.funding = Funding::Credit {
+            credit_type,
+            loan_date: loan_date.to_string(),
+        };
+        self
+    }
+
+    /// 종목코드, 수량, 주문구분에 따른 단가, 신용거래구분 검증
+    pub fn build(self) -> KisResult<OrderRequest> {
+        validate_ticker(&self.ticker)?;
+        if self.quantity == 0 {
+            return Err(Error::InvalidRequest(
+                "order quantity must be greater than 0".to_string(),
+            ));
+        }
+        validate_price(self.order_type, self.price)?;
+        if let Funding::Credit {
+            credit_type,
+            loan_date,
+        } = &self.funding
+        {
+            validate_credit(self.side, *credit_type, loan_date)?;
+        }
+
+        Ok(OrderRequest {
+            ticker: self.ticker,
+            side: self.side,
+            order_type: self.order_type,
+            quantity: self.quantity,
+            price: self.price,
+            funding: self.funding,
+        })
+    }
+}
+
+/// 종목코드 (단축코드 6자리)
+pub(crate) fn validate_ticker(ticker: &str) -> KisResult<()> {
+    if ticker.len() != 6 || !ticker.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(Error::InvalidRequest(format!("invalid ticker: {ticker:?}")));
+    }
+    Ok(())
+}
+
+/// 지정가 계열은 단가 필수, 시장가 계열은 단가 0
+pub(crate) fn validate_price(order_type: OrderType, price: u32) -> KisResult<()> {
+    match (order_type.requires_price(), price) {
+        (true, 0) => Err(Error::InvalidRequest(format!(
+            "{order_type:?} order requires a price"
+        ))),
+        (false, p) if p != 0 => Err(Error::InvalidRequest(format!(
+            "{order_type:?} order must be sent with price 0, got {p}"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// 매수/매도에 맞는 신용거래구분, 상환 주문의 대출일자
+fn validate_credit(side: OrderSide, credit_type: CreditType, loan_date: &str) -> KisResult<()> {
+    if credit_type.side() != side {
+        return Err(Error::InvalidRequest(format!(
+            "{credit_type:?} can not be used for {side:?} order"
+        )));
+    }
+    if !(loan_date.is_empty() && credit_type.is_new()) {
+        validate_date(loan_date)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+
+    #[test]
+    fn test_order_ack() {
+        let v = serde_json::json!({
+            "KRX_FWDG_ORD_ORGNO": "91252",
+            "ODNO": "0000117057",
+            "ORD_TMD": "121052"
+        });
+        let ack: OrderAck = serde_json::from_value(v).unwrap();
+        assert_eq!(ack.krx_orgno, "91252");
+        assert_eq!(ack.order_no, "0000117057");
+        assert_eq!(ack.order_time, "121052");
+    }
+
+    #[test]
+    fn test_order_type_code() {
+        for code in [
+            "00", "01", "02", "03", "04", "05", "06", "07", "11", "12", "13", "14", "15", "16",
+        ] {
+            assert_eq!(OrderType::from_code(code).unwrap().code(), code);
+        }
+        assert_eq!(OrderType::from_code("99"), None);
+
+        assert_eq!(
+            OrderSide::from_code(OrderSide::Buy.code()),
+            Some(OrderSide::Buy)
+        );
+        assert_eq!(
+            OrderSide::from_code(OrderSide::Sell.code()),
+            Some(OrderSide::Sell)
+        );
+    }
+
+    #[test]
+    fn test_build_limit_order() {
+        let order = OrderRequest::buy("005930")
+            .order_type(OrderType::Limit)
+            .quantity(10)
+            .price(70000)
+            .build()
+            .unwrap();
+        assert_eq!(order.get_side(), OrderSide::Buy);
+        assert_eq!(order.get_order_type().code(), "00");
+        assert_eq!(order.get_quantity(), 10);
+        assert_eq!(order.get_price(), 70000);
+
+        let res = OrderRequest::buy("005930").quantity(10).build();
+        assert!(matches!(res, Err(Error::InvalidRequest(_))));
+    }
+
+    #[test]
+    fn test_build_market_order() {
+        let order = OrderRequest::sell("005930")
+            .order_type(OrderType::Market)
+            .quantity(1)
+            .build();
+        assert!(order.is_ok());
+
+        let res = OrderRequest::sell("005930")
+            .order_type(OrderType::IocMarket)
+            .quantity(1)
+            .price(70000)
+            .build();
+        assert!(matches!(res, Err(Error::InvalidRequest(_))));
+    }
+
+    #[test]
+    fn test_build_invalid_order() {
+        let res = OrderRequest::buy("005930")
+            .order_type(OrderType::Market)
+            .build();
+        assert!(matches!(res, Err(Error::InvalidRequest(_))));
+
+        let res = OrderRequest::buy("5930")
+            .order_type(OrderType::Market)
+            .quantity(1)
+            .build();
+        assert!(matches!(res, Err(Error::InvalidRequest(_))));
+    }
+
+    #[test]
+    fn test_reservation_order() {
+        let order = OrderRequest::buy("005930")
+            .quantity(10)
+            .price(70000)
+            .build()
+            .unwrap();
+        let reservation = ReservationOrder::new(order.clone()).end_date("20240131");
+        assert!(reservation.validate().is_ok());
+        assert_eq!(reservation.get_order(), &order);
+
+        let res = ReservationOrder::new(order).end_date("2024-01-31");
+        assert!(matches!(res.validate(), Err(Error::InvalidRequest(_))));
+
+        let ioc = OrderRequest::buy("005930")
+            .order_type(OrderType::IocMarket)
+            .quantity(10)
+            .build()
+            .unwrap();
+        assert!(matches!(
+            ReservationOrder::new(ioc).validate(),
+            Err(Error::InvalidRequest(_))
+        ));
+
+        // 현금 예약으로 바뀌어 접수되지 않도록 거절
+        let credit = OrderRequest::buy("005930")
+            .quantity(10)
+            .price(70000)
+            .credit(CreditType::OwnLoanNew, "")
+            .build()
+            .unwrap();
+        assert!(matches!(
+            ReservationOrder::new(credit).validate(),
+            Err(Error::InvalidRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_build_credit_order() {
+        let order = OrderRequest::buy("005930")
+            .quantity(10)
+            .price(70000)
+            .credit(CreditType::OwnLoanNew, "")
+            .build()
+            .unwrap();
+        assert!(
+            matches!(order.get_funding(), Funding::Credit { credit_type, .. } if credit_type.code() == "21")
+        );
+
+        let order = OrderRequest::sell("005930")
+            .order_type(OrderType::Market)
+            .quantity(10)
+            .credit(CreditType::OwnLoanRepay, "20240105")
+            .build();
+        assert!(order.is_ok());
+
+        for res in [
+            // 융자신규는 매수만
+            OrderRequest::sell("005930")
+                .quantity(10)
+                .price(70000)
+                .credit(CreditType::OwnLoanNew, "")
+                .build(),
+            // 상환은 대출일자 필수
+            OrderRequest::sell("005930")
+                .quantity(10)
+                .price(70000)
+                .credit(CreditType::OwnLoanRepay, "")
+                .build(),
+        ] {
+            assert!(matches!(res, Err(Error::InvalidRequest(_))));
+        }
+    }
+}
@@ -0,0 +1,448 @@
+//! 국내주식시세 응답 타입
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use super::de;
+use super::response::take_output;
+use super::KisResult;
+
+/// 주식현재가 시세[v1_국내주식-008] output
+#[derive(Debug, Clone, Deserialize)]
+pub struct CurrentPrice {
+    /// 주식 현재가
+    #[serde(rename = "stck_prpr", deserialize_with = "de::number")]
+    pub price: i64,
+    /// 전일 대비
+    #[serde(rename = "prdy_vrss", deserialize_with = "de::number")]
+    pub change: i64,
+    /// 전일 대비 부호 (1:상한 2:상승 3:보합 4:하한 5:하락)
+    #[serde(rename = "prdy_vrss_sign", default)]
+    pub change_sign: String,
+    /// 전일 대비율
+    #[serde(rename = "prdy_ctrt", deserialize_with = "de::number")]
+    pub change_rate: f64,
+    /// 누적 거래량
+    #[serde(rename = "acml_vol", deserialize_with = "de::number")]
+    pub volume: u64,
+    /// 누적 거래 대금
+    #[serde(rename = "acml_tr_pbmn", deserialize_with = "de::number")]
+    pub trade_amount: u64,
+    /// 주식 시가
+    #[serde(rename = "stck_oprc", deserialize_with = "de::number")]
+    pub open: i64,
+    /// 주식 최고가
+    #[serde(rename = "stck_hgpr", deserialize_with = "de::number")]
+    pub high: i64,
+    /// 주식 최저가
+    #[serde(rename = "stck_lwpr", deserialize_with = "de::number")]
+    pub low: i64,
+    /// 주식 상한가
+    #[serde(rename = "stck_mxpr", deserialize_with = "de::number")]
+    pub upper_limit: i64,
+    /// 주식 하한가
+    #[serde(rename = "stck_llam", deserialize_with = "de::number")]
+    pub lower_limit: i64,
+    /// 주식 기준가
+    #[serde(rename = "stck_sdpr", deserialize_with = "de::number")]
+    pub base_price: i64,
+    /// 호가단위
+    #[serde(rename = "aspr_unit", default, deserialize_with = "de::number")]
+    pub tick_size: i64,
+    /// 52주일 최고가
+    #[serde(rename = "w52_hgpr", default, deserialize_with = "de::number")]
+    pub high_52w: i64,
+    /// 52주일 최저가
+    #[serde(rename = "w52_lwpr", default, deserialize_with = "de::number")]
+    pub low_52w: i64,
+    /// HTS 시가총액 (억)
+    #[serde(rename = "hts_avls", default, deserialize_with = "de::number")]
+    pub market_cap: u64,
+    /// 상장 주수
+    #[serde(rename = "lstn_stcn", default, deserialize_with = "de::number")]
+    pub listed_shares: u64,
+    /// HTS 외국인 소진율
+    #[serde(rename = "hts_frgn_ehrt", default, deserialize_with = "de::number")]
+    pub foreign_rate: f64,
+    /// PER
+    #[serde(default, deserialize_with = "de::number")]
+    pub per: f64,
+    /// PBR
+    #[serde(default, deserialize_with = "de::number")]
+    pub pbr: f64,
+    /// EPS
+    #[serde(default, deserialize_with = "de::number")]
+    pub eps: f64,
+    /// BPS
+    #[serde(default, deserialize_with = "de::number")]
+    pub bps: f64,
+    /// 모델링되지 않은 나머지 필드
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// 주식현재가 체결[v1_국내주식-009] output
+#[derive(Debug, Clone, Deserialize)]
+pub struct Conclusion {
+    /// 주식 체결 시간 (HHMMSS)
+    #[serde(rename = "stck_cntg_hour")]
+    pub time: String,
+    /// 주식 현재가
+    #[serde(rename = "stck_prpr", deserialize_with = "de::number")]
+    pub price: i64,
+    /// 전일 대비
+    #[serde(rename = "prdy_vrss", deserialize_with = "de::number")]
+    pub change: i64,
+    /// 전일 대비 부호
+    #[serde(rename = "prdy_vrss_sign", default)]
+    pub change_sign: String,
+    /// 전일 대비율
+    #[serde(rename = "prdy_ctrt", default, deserialize_with = "de::number")]
+    pub change_rate: f64,
+    /// 체결 거래량
+    #[serde(rename = "cntg_vol", deserialize_with = "de::number")]
+    pub volume: u64,
+    /// 당일 체결강도
+    #[serde(rename = "tday_rltv", default, deserialize_with = "de::number")]
+    pub strength: f64,
+    /// 모델링되지 않은 나머지 필드
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// 주식현재가 일자별[v1_국내주식-010] output
+#[derive(Debug, Clone, Deserialize)]
+pub struct DailyPrice {
+    /// 주식 영업 일자 (YYYYMMDD)
+    #[serde(rename = "stck_bsop_date")]
+    pub date: String,
+    /// 주식 시가
+    #[serde(rename = "stck_oprc", deserialize_with = "de::number")]
+    pub open: i64,
+    /// 주식 최고가
+    #[serde(rename = "stck_hgpr", deserialize_with = "de::number")]
+    pub high: i64,
+    /// 주식 최저가
+    #[serde(rename = "stck_lwpr", deserialize_with = "de::number")]
+    pub low: i64,
+    /// 주식 종가
+    #[serde(rename = "stck_clpr", deserialize_with = "de::number")]
+    pub close: i64,
+    /// 누적 거래량
+    #[serde(rename = "acml_vol", deserialize_with = "de::number")]
+    pub volume: u64,
+    /// 전일 대비
+    #[serde(rename = "prdy_vrss", default, deserialize_with = "de::number")]
+    pub change: i64,
+    /// 전일 대비 부호
+    #[serde(rename = "prdy_vrss_sign", default)]
+    pub change_sign: String,
+    /// 전일 대비율
+    #[serde(rename = "prdy_ctrt", default, deserialize_with = "de::number")]
+    pub change_rate: f64,
+    /// HTS 외국인 소진율
+    #[serde(rename = "hts_frgn_ehrt", default, deserialize_with = "de::number")]
+    pub foreign_rate: f64,
+    /// 외국인 순매수 수량
+    #[serde(rename = "frgn_ntby_qty", default, deserialize_with = "de::number")]
+    pub foreign_net_buy: i64,
+    /// 모델링되지 않은 나머지 필드
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// 호가 한 단계 (가격, 잔량)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PriceLevel {
+    pub price: i64,
+    pub quantity: i64,
+}
+
+/// 주식현재가 호가 예상체결[v1_국내주식-011] output1 : 호가
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "Map<String, Value>")]
+pub struct OrderBook {
+    /// 호가 접수 시간 (HHMMSS)
+    pub time: String,
+    /// 매도호가 1~10 (index 0 이 최우선 호가)
+    pub asks: Vec<PriceLevel>,
+    /// 매수호가 1~10 (index 0 이 최우선 호가)
+    pub bids: Vec<PriceLevel>,
+    /// 총 매도호가 잔량
+    pub total_ask_quantity: i64,
+    /// 총 매수호가 잔량
+    pub total_bid_quantity: i64,
+    /// 원본 필드
+    pub raw: Map<String, Value>,
+}
+
+impl TryFrom<Map<String, Value>> for OrderBook {
+    type Error = String;
+
+    fn try_from(raw: Map<String, Value>) -> Result<Self, Self::Error> {
+        let level = |price: &str, qty: &str| -> Result<PriceLevel, String> {
+            Ok(PriceLevel {
+                price: field(&raw, price)?,
+                quantity: field(&raw, qty)?,
+            })
+        };
+        let asks = (1..=10)
+            .map(|i| level(&format!("askp{i}"), &format!("askp_rsqn{i}")))
+            .collect::<Result<Vec<_>, _>>()?;
+        let bids = (1..=10)
+            .map(|i| level(&format!("bidp{i}"), &format!("bidp_rsqn{i}")))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            time: text(&raw, "aspr_acpt_hour"),
+            asks,
+            bids,
+            total_ask_quantity: field(&raw, "total_askp_rsqn")?,
+            total_bid_quantity: field(&raw, "total_bidp_rsqn")?,
+            raw,
+        })
+    }
+}
+
+/// 주식현재가 호가 예상체결[v1_국내주식-011] output2 : 예상체결
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExpectedConclusion {
+    /// 예상 장운영 구분 코드
+    #[serde(rename = "antc_mkop_cls_code", default)]
+    pub market_operation_code: String,
+    /// 주식 현재가
+    #[serde(rename = "stck_prpr", default, deserialize_with = "de::number")]
+    pub price: i64,
+    /// 예상 체결가
+    #[serde(rename = "antc_cnpr", default, deserialize_with = "de::number")]
+    pub expected_price: i64,
+    /// 예상 체결 대비
+    #[serde(rename = "antc_cntg_vrss", default, deserialize_with = "de::number")]
+    pub expected_change: i64,
+    /// 예상 체결 대비 부호
+    #[serde(rename = "antc_cntg_vrss_sign", default)]
+    pub expected_change_sign: String,
+    /// 예상 체결 전일 대비율
+    #[serde(
+        rename = "antc_cntg_prdy_ctrt",
+        default,
+        deserialize_with = "de::number"
+    )]
+    pub expected_change_rate: f64,
+    /// 예상 거래량
+    #[serde(rename = "antc_vol", default, deserialize_with = "de::number")]
+    pub expected_volume: u64,
+    /// 모델링되지 않은 나머지 필드
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// 주식현재가 호가 예상체결[v1_국내주식-011]
+#[derive(Debug, Clone)]
+pub struct BidAskPrices {
+    pub order_book: OrderBook,
+    pub expected: ExpectedConclusion,
+}
+
+impl BidAskPrices {
+    /// output1 호가, output2 예상체결
+    pub(crate) fn from_response(mut v: Value) -> KisResult<Self> {
+        Ok(BidAskPrices {
+            order_book: take_output(&mut v, "output1")?,
+            expected: take_output(&mut v, "output2")?,
+        })
+    }
+}
+
+/// 주식현재가 투자자[v1_국내주식-012] output
+#[derive(Debug, Clone, Deserialize)]
+pub struct InvestorTrading {
+    /// 주식 영업 일자 (YYYYMMDD)
+    #[serde(rename = "stck_bsop_date")]
+    pub date: String,
+    /// 주식 종가
+    #[serde(rename = "stck_clpr", default, deserialize_with = "de::number")]
+    pub close: i64,
+    /// 전일 대비
+    #[serde(rename = "prdy_vrss", default, deserialize_with = "de::number")]
+    pub change: i64,
+    /// 전일 대비 부호
+    #[serde(rename = "prdy_vrss_sign", default)]
+    pub change_sign: String,
+    /// 개인 순매수 수량
+    #[serde(rename = "prsn_ntby_qty", default, deserialize_with = "de::number")]
+    pub individual_net_buy: i64,
+    /// 외국인 순매수 수량
+    #[serde(rename = "frgn_ntby_qty", default, deserialize_with = "de::number")]
+    pub foreign_net_buy: i64,
+    /// 기관계 순매수 수량
+    #[serde(rename = "orgn_ntby_qty", default, deserialize_with = "de::number")]
+    pub institution_net_buy: i64,
+    /// 개인 순매수 거래 대금
+    #[serde(rename = "prsn_ntby_tr_pbmn", default, deserialize_with = "de::number")]
+    pub individual_net_buy_amount: i64,
+    /// 외국인 순매수 거래 대금
+    #[serde(rename = "frgn_ntby_tr_pbmn", default, deserialize_with = "de::number")]
+    pub foreign_net_buy_amount: i64,
+    /// 기관계 순매수 거래 대금
+    #[serde(rename = "orgn_ntby_tr_pbmn", default, deserialize_with = "de::number")]
+    pub institution_net_buy_amount: i64,
+    /// 모델링되지 않은 나머지 필드
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// 회원사별 거래량
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemberVolume {
+    /// 회원사 번호
+    pub member_no: String,
+    /// 회원사 명
+    pub member_name: String,
+    /// 총 거래 수량
+    pub quantity: i64,
+    /// 거래 비중
+    pub ratio: f64,
+    /// 수량 증감
+    pub quantity_change: i64,
+}
+
+/// 주식현재가 회원사[v1_국내주식-013] output
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "Map<String, Value>")]
+pub struct MemberTrading {
+    /// 매도 상위 회원사 1~5
+    pub sellers: Vec<MemberVolume>,
+    /// 매수 상위 회원사 1~5
+    pub buyers: Vec<MemberVolume>,
+    /// 외국계 총 매도 수량
+    pub foreign_sell_quantity: i64,
+    /// 외국계 총 매수 수량
+    pub foreign_buy_quantity: i64,
+    /// 외국계 순매수 수량
+    pub foreign_net_buy: i64,
+    /// 원본 필드
+    pub raw: Map<String, Value>,
+}
+
+impl TryFrom<Map<String, Value>> for MemberTrading {
+    type Error = String;
+
+    fn try_from(raw: Map<String, Value>) -> Result<Self, Self::Error> {
+        let members = |side: &str, qty: &str| -> Result<Vec<MemberVolume>, String> {
+            (1..=5)
+                .map(|i| {
+                    Ok(MemberVolume {
+                        member_no: text(&raw, &format!("{side}_mbcr_no{i}")),
+                        member_name: text(&raw, &format!("{side}_mbcr_name{i}")),
+                        quantity: field(&raw, &format!("total_{qty}_qty{i}"))?,
+                        ratio: field(&raw, &format!("{side}_mbcr_rlim{i}"))?,
+                        quantity_change: field(&raw, &format!("{qty}_qty_icdc{i}"))?,
+                    })
+                })
+                .filter(|m| !matches!(m, Ok(m) if m.member_no.is_empty()))
+                .collect()
+        };
+
+        Ok(Self {
+            sellers: members("seln", "seln")?,
+            buyers: members("shnu", "shnu")?,
+            foreign_sell_quantity: field(&raw, "glob_total_seln_qty")?,
+            foreign_buy_quantity: field(&raw, "glob_total_shnu_qty")?,
+            foreign_net_buy: field(&raw, "glob_ntby_qty")?,
+            raw,
+        })
+    }
+}
+
+/// 숫자 필드 조회, 없으면 기본값
+fn field<T>(raw: &Map<String, Value>, key: &str) -> Result<T, String>
+where
+    T: std::str::FromStr + Default,
+    T::Err: std::fmt::Display,
+{
+    match raw.get(key) {
+        Some(v) => de::parse_value(v).map_err(|e| format!("{key}: {e}")),
+        None => Ok(T::default()),
+    }
+}
+
+/// 문자열 필드 조회, 없으면 빈 문자열
+fn text(raw: &Map<String, Value>, key: &str) -> String {
+    raw.get(key)
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_current_price() {
+        let v = json!({
+            "stck_prpr": "70000", "prdy_vrss": "-500", "prdy_vrss_sign": "5",
+            "prdy_ctrt": "-0.71", "acml_vol": "1234567", "acml_tr_pbmn": "86419690000",
+            "stck_oprc": "70500", "stck_hgpr": "71000", "stck_lwpr": "69800",
+            "stck_mxpr": "91600", "stck_llam": "49400", "stck_sdpr": "70500",
+            "per": "11.52", "pbr": "1.45", "eps": "6075.00", "bps": "48292.00",
+            "bstp_kor_isnm": "전기.전자"
+        });
+        let price: CurrentPrice = serde_json::from_value(v).unwrap();
+        assert_eq!(price.price, 70000);
+        assert_eq!(price.change, -500);
+        assert_eq!(price.change_rate, -0.71);
+        assert_eq!(price.volume, 1234567);
+        assert_eq!(price.tick_size, 0);
+        assert_eq!(price.extra["bstp_kor_isnm"], "전기.전자");
+    }
+
+    #[test]
+    fn test_order_book() {
+        let mut v = json!({
+            "aspr_acpt_hour": "093015",
+            "total_askp_rsqn": "1500",
+            "total_bidp_rsqn": "2500",
+        });
+        for i in 1..=10 {
+            v[format!("askp{i}")] = json!(format!("{}", 70000 + i * 100));
+            v[format!("askp_rsqn{i}")] = json!(format!("{}", i * 10));
+            v[format!("bidp{i}")] = json!(format!("{}", 70000 - (i - 1) * 100));
+            v[format!("bidp_rsqn{i}")] = json!(format!("{}", i * 20));
+        }
+        let book: OrderBook = serde_json::from_value(v).unwrap();
+        assert_eq!(book.time, "093015");
+        assert_eq!(book.asks.len(), 10);
+        assert_eq!(
+            book.asks[0],
+            PriceLevel {
+                price: 70100,
+                quantity: 10
+            }
+        );
+        assert_eq!(book.bids[9].price, 69100);
+        assert_eq!(book.total_bid_quantity, 2500);
+    }
+
+    #[test]
+    fn test_member_trading() {
+        let v = json!({
+            "seln_mbcr_no1": "00036", "seln_mbcr_name1": "키움증권",
+            "total_seln_qty1": "38000", "seln_mbcr_rlim1": "25.10", "seln_qty_icdc1": "1200",
+            "seln_mbcr_no2": "", "seln_mbcr_name2": "",
+            "shnu_mbcr_no1": "00005", "shnu_mbcr_name1": "미래에셋",
+            "total_shnu_qty1": "41000", "shnu_mbcr_rlim1": "27.00", "shnu_qty_icdc1": "-300",
+            "glob_total_seln_qty": "1000", "glob_total_shnu_qty": "3000", "glob_ntby_qty": "2000"
+        });
+        let member: MemberTrading = serde_json::from_value(v).unwrap();
+        assert_eq!(member.sellers.len(), 1);
+        assert_eq!(member.sellers[0].member_name, "키움증권");
+        assert_eq!(member.buyers[0].quantity_change, -300);
+        assert_eq!(member.foreign_net_buy, 2000);
+    }
+}
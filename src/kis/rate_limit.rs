@@ -0,0 +1,213 @@
+//! KIS 초당 호출 제한에 맞춘 client 측 token bucket
+//!
+//! 제한을 넘으면 KIS 는 `EGW00201` (초당 거래건수 초과) 를 돌려준다.
+
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::{AccountConfig, RequestType};
+
+/// 실전투자 초당 호출 한도
+pub const REAL_CALLS_PER_SEC: u32 = 20;
+/// 모의투자 초당 호출 한도
+pub const VIRTUAL_CALLS_PER_SEC: u32 = 2;
+
+/// 호출 우선순위, 대기 중인 주문이 있으면 시세 조회는 양보
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    /// 주문, hashkey, 인증 요청
+    Order,
+    /// 시세, 잔고 등 조회
+    Quote,
+}
+
+impl From<RequestType> for Priority {
+    fn from(req_type: RequestType) -> Self {
+        match req_type {
+            RequestType::GET => Priority::Quote,
+            _ => Priority::Order,
+        }
+    }
+}
+
+/// thread 간 공유하는 token bucket, 초당 `calls_per_sec` 개씩 채워짐
+#[derive(Debug)]
+pub struct RateLimiter {
+    calls_per_sec: u32,
+    state: Mutex<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    tokens: f64,
+    updated: Instant,
+    /// token 을 기다리는 주문 요청 수
+    orders_waiting: usize,
+}
+
+impl RateLimiter {
+    /// `calls_per_sec` : 초당 호출 한도, 최대 burst 도 같은 값
+    pub fn new(calls_per_sec: u32) -> Self {
+        let calls_per_sec = calls_per_sec.max(1);
+        Self {
+            calls_per_sec,
+            state: Mutex::new(State {
+                tokens: calls_per_sec as f64,
+                updated: Instant::now(),
+                orders_waiting: 0,
+            }),
+        }
+    }
+
+    /// 실전/모의 계좌에 맞는 한도
+    pub fn for_account(conf: &AccountConfig) -> Self {
+        if conf.is_real() {
+            Self::new(REAL_CALLS_PER_SEC)
+        } else {
+            Self::new(VIRTUAL_CALLS_PER_SEC)
+        }
+    }
+
+    pub fn calls_per_sec(&self) -> u32 {
+        self.calls_per_sec
+    }
+
+    /// token 을 얻을 때까지 현재 thread 를 재움
+    pub fn acquire(&self, priority: Priority) {
+        let _waiting = self.enter(priority);
+        while let Err(wait) = self.try_acquire(priority) {
+            thread::sleep(wait);
+        }
+    }
+
+    /// token 을 얻을 때까지 task 를 재움
+    #[cfg(feature = "async")]
+    pub async fn acquire_async(&self, priority: Priority) {
+        let _waiting = self.enter(priority);
+        while let Err(wait) = self.try_acquire(priority) {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// token 을 하나 사용, 없으면 다시 시도할 때까지의 대기 시간
+    ///
+    /// 주문이 기다리는 동안에는 시세 조회에 token 을 주지 않는다.
+    pub fn try_acquire(&self, priority: Priority) -> Result<(), Duration> {
+        self.try_acquire_at(priority, Instant::now())
+    }
+
+    /// `now` 시각 기준 `try_acquire`
+    fn try_acquire_at(&self, priority: Priority, now: Instant) -> Result<(), Duration> {
+        let mut state = self.lock();
+        let per_sec = self.calls_per_sec as f64;
+
+        let elapsed = now.duration_since(state.updated).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * per_sec).min(per_sec);
+        state.updated = now;
+
+        let interval = Duration::from_secs_f64(1.0 / per_sec);
+        if priority == Priority::Quote && state.orders_waiting > 0 {
+            return Err(interval);
+        }
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - state.tokens) / per_sec)
+                .max(Duration::from_millis(1)))
+        }
+    }
+
+    /// 주문 요청은 token 을 얻을 때까지 대기 중으로 표시
+    fn enter(&self, priority: Priority) -> Option<OrderWaiting<'_>> {
+        if priority != Priority::Order {
+            return None;
+        }
+        self.lock().orders_waiting += 1;
+        Some(OrderWaiting(self))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        // 상태는 항상 일관되게 갱신되므로 poison 되어도 그대로 사용
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// drop 될 때 대기 중인 주문 수를 줄임 (async task 가 취소되어도 복구)
+struct OrderWaiting<'a>(&'a RateLimiter);
+
+impl Drop for OrderWaiting<'_> {
+    fn drop(&mut self) {
+        self.0.lock().orders_waiting -= 1;
+    }
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+
+    #[test]
+    fn test_budget_from_account() {
+        let real = AccountConfig::mock("http://localhost", true);
+        let virt = AccountConfig::mock("http://localhost", false);
+        assert_eq!(
+            RateLimiter::for_account(&real).calls_per_sec(),
+            REAL_CALLS_PER_SEC
+        );
+        assert_eq!(
+            RateLimiter::for_account(&virt).calls_per_sec(),
+            VIRTUAL_CALLS_PER_SEC
+        );
+    }
+
+    #[test]
+    fn test_token_bucket() {
+        let limiter = RateLimiter::new(2);
+        assert!(limiter.try_acquire(Priority::Quote).is_ok());
+        assert!(limiter.try_acquire(Priority::Quote).is_ok());
+
+        let wait = limiter.try_acquire(Priority::Quote).unwrap_err();
+        assert!(wait > Duration::from_millis(400) && wait <= Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_order_priority() {
+        let limiter = RateLimiter::new(2);
+        let waiting = limiter.enter(Priority::Order);
+        assert!(limiter.try_acquire(Priority::Quote).is_err());
+        assert!(limiter.try_acquire(Priority::Order).is_ok());
+
+        drop(waiting);
+        assert!(limiter.try_acquire(Priority::Quote).is_ok());
+    }
+
+    #[test]
+    fn test_order_not_starved_by_polling() {
+        let limiter = RateLimiter::new(50);
+        let start = limiter.lock().updated;
+
+        // 시세 조회가 burst 를 모두 사용
+        for _ in 0..50 {
+            assert!(limiter.try_acquire_at(Priority::Quote, start).is_ok());
+        }
+        assert!(limiter.try_acquire_at(Priority::Quote, start).is_err());
+
+        // 주문은 다음 token 하나만 기다림
+        let waiting = limiter.enter(Priority::Order);
+        let wait = limiter.try_acquire_at(Priority::Order, start).unwrap_err();
+        assert!(wait <= Duration::from_millis(20), "{wait:?}");
+
+        // token 이 채워지면 기다리던 시세 조회보다 주문이 먼저
+        let refilled = start + wait + Duration::from_millis(1);
+        assert!(limiter.try_acquire_at(Priority::Quote, refilled).is_err());
+        assert!(limiter.try_acquire_at(Priority::Order, refilled).is_ok());
+        drop(waiting);
+
+        // 주문이 쓴 token 만큼 시세 조회는 다음 token 을 기다림
+        assert!(limiter.try_acquire_at(Priority::Quote, refilled).is_err());
+        assert!(limiter
+            .try_acquire_at(Priority::Quote, refilled + Duration::from_millis(20))
+            .is_ok());
+    }
+}
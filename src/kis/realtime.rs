@@ -0,0 +1,684 @@
+//! 실시간 (웹소켓) 데이터 타입
+//!
+//! 실시간 데이터는 `암호화여부|tr_id|데이터건수|필드^필드^...` 형식이며
+//! 여러 건이 묶여 올 때는 모든 건의 필드가 '^' 로 이어져 온다.
+
+use std::str::FromStr;
+
+use openssl::symm;
+use serde_json::Value;
+
+use super::de;
+use super::order::{OrderSide, OrderType};
+use super::quotation::PriceLevel;
+use super::{Error, KisResult};
+
+/// 실시간 주식호가 (KRX)
+pub const ORDER_BOOK_TR_ID: &str = "H0STASP0";
+/// 실시간 주식체결가 (KRX)
+pub const TICK_TR_ID: &str = "H0STCNT0";
+/// 실시간 체결통보 (실전투자)
+pub const EXECUTION_TR_ID: &str = "H0STCNI0";
+/// 실시간 체결통보 (모의투자)
+pub const VIRTUAL_EXECUTION_TR_ID: &str = "H0STCNI9";
+/// 서버가 주기적으로 보내는 heartbeat, 받은 그대로 돌려보내야 연결이 유지됨
+pub const PINGPONG_TR_ID: &str = "PINGPONG";
+
+/// '|' 로 구분된 실시간 데이터 frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct DataFrame<'a> {
+    /// 암호화된 데이터 (체결통보)
+    pub encrypted: bool,
+    pub tr_id: &'a str,
+    /// 묶여 온 데이터 건수
+    pub count: usize,
+    pub payload: &'a str,
+}
+
+/// 실시간 데이터 frame 해석, 구독 응답이나 PINGPONG 같은 JSON 메시지는 `None`
+pub(crate) fn parse_frame(text: &str) -> KisResult<Option<DataFrame<'_>>> {
+    if !text.starts_with('0') && !text.starts_with('1') {
+        return Ok(None);
+    }
+    let mut parts = text.splitn(4, '|');
+    let (flag, tr_id, count, payload) =
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(flag), Some(tr_id), Some(count), Some(payload)) => (flag, tr_id, count, payload),
+            _ => return Err(Error::Parse(format!("invalid realtime frame: {text:?}"))),
+        };
+    let count = de::parse_str::<usize>(count).map_err(Error::Parse)?;
+
+    Ok(Some(DataFrame {
+        encrypted: flag == "1",
+        tr_id,
+        count: count.max(1),
+        payload,
+    }))
+}
+
+/// 구독 응답, PINGPONG 등 JSON 메시지
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Control {
+    pub tr_id: String,
+    pub tr_key: String,
+    pub rt_cd: String,
+    pub msg_cd: String,
+    pub msg1: String,
+    /// 체결통보처럼 암호화되는 구독의 복호화 key
+    pub cipher: Option<PayloadCipher>,
+}
+
+impl Control {
+    pub fn is_pingpong(&self) -> bool {
+        self.tr_id == PINGPONG_TR_ID
+    }
+
+    /// 등록 (1) / 해제 (2) 응답 구분, msg1 로 알 수 없으면 `None`
+    pub fn tr_type(&self) -> Option<&'static str> {
+        if self.msg1.contains("UNSUBSCRIBE") {
+            Some("2")
+        } else if self.msg1.contains("SUBSCRIBE") {
+            Some("1")
+        } else {
+            None
+        }
+    }
+
+    /// 구독/해제 실패 (PINGPONG 처럼 body 가 없는 메시지는 성공으로 봄)
+    pub fn error(&self) -> Option<Error> {
+        if self.rt_cd.is_empty() || self.rt_cd == "0" {
+            return None;
+        }
+        Some(Error::Api {
+            rt_cd: self.rt_cd.clone(),
+            msg_cd: self.msg_cd.clone(),
+            msg1: self.msg1.clone(),
+        })
+    }
+}
+
+/// JSON 메시지 해석, 실시간 데이터 frame 은 `None`
+pub(crate) fn parse_control(text: &str) -> KisResult<Option<Control>> {
+    if !text.starts_with('{') {
+        return Ok(None);
+    }
+    let v: Value = serde_json::from_str(text)?;
+    let text = |value: &Value| value.as_str().unwrap_or_default().to_string();
+    let output = &v["body"]["output"];
+    let cipher = match (output["key"].as_str(), output["iv"].as_str()) {
+        (Some(key), Some(iv)) => Some(PayloadCipher::new(key, iv)),
+        _ => None,
+    };
+
+    Ok(Some(Control {
+        tr_id: text(&v["header"]["tr_id"]),
+        tr_key: text(&v["header"]["tr_key"]),
+        rt_cd: text(&v["body"]["rt_cd"]),
+        msg_cd: text(&v["body"]["msg_cd"]),
+        msg1: text(&v["body"]["msg1"]),
+        cipher,
+    }))
+}
+
+/// 암호화된 실시간 데이터의 AES-256-CBC key, iv (구독 응답으로 받음)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PayloadCipher {
+    key: String,
+    iv: String,
+}
+
+impl PayloadCipher {
+    pub fn new(key: &str, iv: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            iv: iv.to_string(),
+        }
+    }
+
+    /// base64 로 인코딩된 payload 복호화
+    pub fn decrypt(&self, payload: &str) -> KisResult<String> {
+        let data = base64::decode(payload.trim())
+            .map_err(|e| Error::Parse(format!("invalid base64 payload: {e}")))?;
+        let plain = symm::decrypt(
+            symm::Cipher::aes_256_cbc(),
+            self.key.as_bytes(),
+            Some(self.iv.as_bytes()),
+            &data,
+        )
+        .map_err(|e| Error::Parse(format!("can not decrypt payload: {e}")))?;
+        String::from_utf8(plain).map_err(|e| Error::Parse(format!("invalid payload: {e}")))
+    }
+}
+
+/// 실시간 데이터 한 건, '^' 로 구분된 `FIELDS` 개 이상의 필드
+pub(crate) trait RealtimeRecord: Sized {
+    const TR_ID: &'static str;
+    const FIELDS: usize;
+
+    fn from_fields(fields: &[&str]) -> KisResult<Self>;
+
+    /// 구독할 때 사용한 tr_key (종목코드), 구독자 모두에게 보낼 record 는 `None`
+    fn tr_key(&self) -> Option<&str>;
+}
+
+/// `T` 의 tr_id 인 평문 frame 의 모든 record, 다른 메시지는 빈 목록
+pub(crate) fn parse_text<T: RealtimeRecord>(text: &str) -> KisResult<Vec<T>> {
+    match parse_frame(text)? {
+        Some(frame) if frame.tr_id == T::TR_ID && !frame.encrypted => {
+            parse_records(frame.payload, frame.count)
+        }
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// 복호화된 `payload` 를 `count` 건의 record 로 분리
+pub(crate) fn parse_records<T: RealtimeRecord>(payload: &str, count: usize) -> KisResult<Vec<T>> {
+    let fields: Vec<&str> = payload.split('^').collect();
+    let count = count.max(1);
+    let size = fields.len() / count;
+    if size < T::FIELDS || !fields.len().is_multiple_of(count) {
+        return Err(Error::Parse(format!(
+            "expected {count} records of {} fields, got {} fields",
+            T::FIELDS,
+            fields.len()
+        )));
+    }
+    fields.chunks(size).map(T::from_fields).collect()
+}
+
+/// index 로 필드를 읽는 helper, 숫자 변환 에러에 필드 위치를 붙임
+struct Fields<'a>(&'a [&'a str]);
+
+impl Fields<'_> {
+    fn text(&self, i: usize) -> String {
+        self.0[i].trim().to_string()
+    }
+
+    fn num<T>(&self, i: usize) -> KisResult<T>
+    where
+        T: FromStr + Default,
+        T::Err: std::fmt::Display,
+    {
+        de::parse_str(self.0[i]).map_err(|e| Error::Parse(format!("field {i}: {e}")))
+    }
+
+    fn levels(&self, price: usize, quantity: usize) -> KisResult<Vec<PriceLevel>> {
+        (0..10)
+            .map(|i| {
+                Ok(PriceLevel {
+                    price: self.num(price + i)?,
+                    quantity: self.num(quantity + i)?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// 실시간 주식호가 (H0STASP0)
+#[derive(Debug, Clone, PartialEq)]
+pub struct RealtimeOrderBook {
+    /// 종목코드
+    pub ticker: String,
+    /// 영업시간 (HHMMSS)
+    pub time: String,
+    /// 시간구분코드 (0 장중, A 장후예상, B 장전예상, C 9시이후 예상/VI, D 시간외 단일가 예상)
+    pub hour_code: String,
+    /// 매도호가 1~10 (index 0 이 최우선 호가)
+    pub asks: Vec<PriceLevel>,
+    /// 매수호가 1~10 (index 0 이 최우선 호가)
+    pub bids: Vec<PriceLevel>,
+    /// 총 매도호가 잔량
+    pub total_ask_quantity: i64,
+    /// 총 매수호가 잔량
+    pub total_bid_quantity: i64,
+    /// 총 매도호가 잔량 증감
+    pub total_ask_change: i64,
+    /// 총 매수호가 잔량 증감
+    pub total_bid_change: i64,
+    /// 시간외 총 매도호가 잔량
+    pub overtime_ask_quantity: i64,
+    /// 시간외 총 매수호가 잔량
+    pub overtime_bid_quantity: i64,
+    /// 예상 체결가
+    pub expected_price: i64,
+    /// 예상 체결량
+    pub expected_quantity: i64,
+    /// 예상 거래량
+    pub expected_volume: i64,
+    /// 예상 체결 대비
+    pub expected_change: i64,
+    /// 예상 체결 대비 부호
+    pub expected_change_sign: String,
+    /// 예상 체결 전일 대비율
+    pub expected_change_rate: f64,
+    /// 누적 거래량
+    pub volume: i64,
+    /// 주식 매매 구분 코드
+    pub deal_code: String,
+}
+
+impl RealtimeRecord for RealtimeOrderBook {
+    const TR_ID: &'static str = ORDER_BOOK_TR_ID;
+    const FIELDS: usize = 59;
+
+    fn from_fields(fields: &[&str]) -> KisResult<Self> {
+        let f = Fields(fields);
+        Ok(RealtimeOrderBook {
+            ticker: f.text(0),
+            time: f.text(1),
+            hour_code: f.text(2),
+            asks: f.levels(3, 23)?,
+            bids: f.levels(13, 33)?,
+            total_ask_quantity: f.num(43)?,
+            total_bid_quantity: f.num(44)?,
+            overtime_ask_quantity: f.num(45)?,
+            overtime_bid_quantity: f.num(46)?,
+            expected_price: f.num(47)?,
+            expected_quantity: f.num(48)?,
+            expected_volume: f.num(49)?,
+            expected_change: f.num(50)?,
+            expected_change_sign: f.text(51),
+            expected_change_rate: f.num(52)?,
+            volume: f.num(53)?,
+            total_ask_change: f.num(54)?,
+            total_bid_change: f.num(55)?,
+            deal_code: f.text(58),
+        })
+    }
+
+    fn tr_key(&self) -> Option<&str> {
+        Some(&self.ticker)
+    }
+}
+
+impl RealtimeOrderBook {
+    /// H0STASP0 frame 의 모든 호가
+    pub fn parse(text: &str) -> KisResult<Vec<Self>> {
+        parse_text(text)
+    }
+}
+
+/// 실시간 주식체결가 (H0STCNT0)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tick {
+    /// 종목코드
+    pub ticker: String,
+    /// 영업일자 (YYYYMMDD)
+    pub date: String,
+    /// 체결시간 (HHMMSS)
+    pub time: String,
+    /// 현재가 (체결가)
+    pub price: i64,
+    /// 전일 대비
+    pub change: i64,
+    /// 전일 대비 부호 (1 상한, 2 상승, 3 보합, 4 하한, 5 하락)
+    pub change_sign: String,
+    /// 전일 대비율
+    pub change_rate: f64,
+    /// 시가
+    pub open: i64,
+    /// 고가
+    pub high: i64,
+    /// 저가
+    pub low: i64,
+    /// 매도호가1
+    pub ask: i64,
+    /// 매수호가1
+    pub bid: i64,
+    /// 체결 거래량
+    pub volume: i64,
+    /// 누적 거래량
+    pub cumulative_volume: i64,
+    /// 누적 거래대금
+    pub cumulative_amount: i64,
+    /// 체결강도
+    pub strength: f64,
+    /// 체결구분, 매수 (1) 또는 매도 (5) 주도 체결, 장전 (3) 은 `None`
+    pub side: Option<OrderSide>,
+}
+
+impl RealtimeRecord for Tick {
+    const TR_ID: &'static str = TICK_TR_ID;
+    const FIELDS: usize = 46;
+
+    fn from_fields(fields: &[&str]) -> KisResult<Self> {
+        let f = Fields(fields);
+        let side = match fields[21].trim() {
+            "1" => Some(OrderSide::Buy),
+            "5" => Some(OrderSide::Sell),
+            _ => None,
+        };
+        Ok(Tick {
+            ticker: f.text(0),
+            date: f.text(33),
+            time: f.text(1),
+            price: f.num(2)?,
+            change_sign: f.text(3),
+            change: f.num(4)?,
+            change_rate: f.num(5)?,
+            open: f.num(7)?,
+            high: f.num(8)?,
+            low: f.num(9)?,
+            ask: f.num(10)?,
+            bid: f.num(11)?,
+            volume: f.num(12)?,
+            cumulative_volume: f.num(13)?,
+            cumulative_amount: f.num(14)?,
+            strength: f.num(18)?,
+            side,
+        })
+    }
+
+    fn tr_key(&self) -> Option<&str> {
+        Some(&self.ticker)
+    }
+}
+
+impl Tick {
+    /// H0STCNT0 frame 의 모든 체결
+    pub fn parse(text: &str) -> KisResult<Vec<Self>> {
+        parse_text(text)
+    }
+}
+
+/// 실시간 체결통보 (H0STCNI0, H0STCNI9), 주문 접수/정정/취소/거부와 체결
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutionNotice {
+    /// 고객 ID
+    pub customer_id: String,
+    /// 계좌번호
+    pub account: String,
+    /// 주문번호
+    pub order_no: String,
+    /// 원주문번호, 정정/취소 주문에만 있음
+    pub original_order_no: String,
+    /// 매도매수구분
+    pub side: OrderSide,
+    /// 정정구분 (0 정상, 1 정정, 2 취소)
+    pub revision_code: String,
+    /// 주문종류, 알 수 없는 코드는 `None`
+    pub order_type: Option<OrderType>,
+    /// 종목코드
+    pub ticker: String,
+    /// 종목명
+    pub name: String,
+    /// 체결수량 (접수 통보는 주문수량)
+    pub filled_quantity: u64,
+    /// 체결단가
+    pub filled_price: i64,
+    /// 체결시간 (HHMMSS)
+    pub time: String,
+    /// 주문 거부
+    pub rejected: bool,
+    /// 체결 통보 (아니면 접수 통보)
+    pub filled: bool,
+    /// 접수여부 (1 주문접수, 2 확인, 3 취소(IOC/FOK))
+    pub accept_code: String,
+    /// 지점번호
+    pub branch_no: String,
+    /// 주문수량
+    pub order_quantity: u64,
+    /// 주문가격, 없으면 0
+    pub order_price: i64,
+}
+
+impl RealtimeRecord for ExecutionNotice {
+    const TR_ID: &'static str = EXECUTION_TR_ID;
+    const FIELDS: usize = 19;
+
+    fn from_fields(fields: &[&str]) -> KisResult<Self> {
+        let f = Fields(fields);
+        let side = OrderSide::from_code(fields[4].trim())
+            .ok_or_else(|| Error::Parse(format!("invalid side code {:?}", fields[4])))?;
+        Ok(ExecutionNotice {
+            customer_id: f.text(0),
+            account: f.text(1),
+            order_no: f.text(2),
+            original_order_no: f.text(3),
+            side,
+            revision_code: f.text(5),
+            order_type: OrderType::from_code(fields[6].trim()),
+            ticker: f.text(8),
+            filled_quantity: f.num(9)?,
+            filled_price: f.num(10)?,
+            time: f.text(11),
+            rejected: fields[12].trim() == "1",
+            filled: fields[13].trim() == "2",
+            accept_code: f.text(14),
+            branch_no: f.text(15),
+            order_quantity: f.num(16)?,
+            name: f.text(18),
+            order_price: match fields.get(22) {
+                Some(_) => f.num(22)?,
+                None => 0,
+            },
+        })
+    }
+
+    /// 계좌 하나의 체결통보만 구독하므로 모두 전달
+    fn tr_key(&self) -> Option<&str> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+
+    /// 호가 1~10 은 70100.. / 70000.., 잔량은 index 값
+    fn mock_order_book(ticker: &str, time: &str) -> String {
+        let mut fields: Vec<String> = vec![ticker.to_string(), time.to_string(), "0".to_string()];
+        fields.extend((0..10).map(|i| (70100 + i * 100).to_string()));
+        fields.extend((0..10).map(|i| (70000 - i * 100).to_string()));
+        fields.extend((23..43).map(|i| i.to_string()));
+        fields.extend(
+            [
+                "1000", "2000", "0", "0", "70000", "10", "500", "-100", "5", "-0.14", "123456",
+                "-5", "7", "0", "0", "0",
+            ]
+            .map(String::from),
+        );
+        fields.join("^")
+    }
+
+    #[test]
+    fn test_parse_frame() {
+        assert_eq!(
+            parse_frame(r#"{"header":{"tr_id":"PINGPONG"}}"#).unwrap(),
+            None
+        );
+
+        let frame = parse_frame("1|H0STCNI0|001|encrypted").unwrap().unwrap();
+        assert!(frame.encrypted);
+        assert_eq!(frame.tr_id, "H0STCNI0");
+        assert_eq!(frame.payload, "encrypted");
+
+        assert!(parse_frame("0|H0STASP0").is_err());
+    }
+
+    #[test]
+    fn test_realtime_order_book() {
+        let text = format!("0|H0STASP0|001|{}", mock_order_book("005930", "093001"));
+        let books = RealtimeOrderBook::parse(&text).unwrap();
+        assert_eq!(books.len(), 1);
+
+        let book = &books[0];
+        assert_eq!(book.ticker, "005930");
+        assert_eq!(
+            book.asks[0],
+            PriceLevel {
+                price: 70100,
+                quantity: 23
+            }
+        );
+        assert_eq!(
+            book.bids[9],
+            PriceLevel {
+                price: 69100,
+                quantity: 42
+            }
+        );
+        assert_eq!(book.total_ask_quantity, 1000);
+        assert_eq!(book.expected_price, 70000);
+        assert_eq!(book.expected_change_rate, -0.14);
+        assert_eq!(book.volume, 123456);
+        assert_eq!(book.total_bid_change, 7);
+    }
+
+    #[test]
+    fn test_batched_order_book() {
+        let text = format!(
+            "0|H0STASP0|002|{}^{}",
+            mock_order_book("005930", "093001"),
+            mock_order_book("005930", "093002")
+        );
+        let books = RealtimeOrderBook::parse(&text).unwrap();
+        let times: Vec<_> = books.iter().map(|b| b.time.as_str()).collect();
+        assert_eq!(times, ["093001", "093002"]);
+
+        // 건수와 필드 수가 맞지 않음
+        let text = format!("0|H0STASP0|003|{}", mock_order_book("005930", "093001"));
+        assert!(matches!(
+            RealtimeOrderBook::parse(&text),
+            Err(Error::Parse(_))
+        ));
+        assert!(RealtimeOrderBook::parse("0|H0STCNT0|001|005930")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_tick() {
+        let tick = |time: &str, side: &str| {
+            let mut fields = vec!["0"; 46];
+            fields[0] = "005930";
+            fields[1] = time;
+            fields[2] = "70100";
+            fields[3] = "2";
+            fields[4] = "600";
+            fields[5] = "0.86";
+            fields[12] = "15";
+            fields[13] = "1234567";
+            fields[18] = "112.35";
+            fields[21] = side;
+            fields[33] = "20240105";
+            fields.join("^")
+        };
+        let text = format!(
+            "0|H0STCNT0|003|{}^{}^{}",
+            tick("093001", "1"),
+            tick("093002", "5"),
+            tick("085959", "3")
+        );
+        let ticks = Tick::parse(&text).unwrap();
+        assert_eq!(ticks.len(), 3);
+        assert_eq!(ticks[0].price, 70100);
+        assert_eq!(ticks[0].change, 600);
+        assert_eq!(ticks[0].volume, 15);
+        assert_eq!(ticks[0].cumulative_volume, 1234567);
+        assert_eq!(ticks[0].strength, 112.35);
+        assert_eq!(ticks[0].date, "20240105");
+        let sides: Vec<_> = ticks.iter().map(|t| t.side).collect();
+        assert_eq!(sides, [Some(OrderSide::Buy), Some(OrderSide::Sell), None]);
+
+        // 호가 frame 은 무시
+        let text = format!("0|H0STASP0|001|{}", mock_order_book("005930", "093001"));
+        assert!(Tick::parse(&text).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_control() {
+        let ack = r#"{"header":{"tr_id":"H0STCNI9","tr_key":"hts-id","encrypt":"N"},"body":{"rt_cd":"0","msg_cd":"OPSP0000","msg1":"SUBSCRIBE SUCCESS","output":{"iv":"0123456789abcdef","key":"0123456789abcdef0123456789abcdef"}}}"#;
+        let control = parse_control(ack).unwrap().unwrap();
+        assert_eq!(control.tr_id, "H0STCNI9");
+        assert!(control.error().is_none());
+        assert_eq!(control.tr_type(), Some("1"));
+        assert_eq!(
+            control.cipher,
+            Some(PayloadCipher::new(
+                "0123456789abcdef0123456789abcdef",
+                "0123456789abcdef"
+            ))
+        );
+
+        let ping = parse_control(r#"{"header":{"tr_id":"PINGPONG","datetime":"20240105093001"}}"#)
+            .unwrap()
+            .unwrap();
+        assert_eq!(ping.tr_id, "PINGPONG");
+        assert!(ping.is_pingpong());
+        assert_eq!(ping.tr_type(), None);
+
+        let unsubscribed = r#"{"header":{"tr_id":"H0STCNT0","tr_key":"005930","encrypt":"N"},"body":{"rt_cd":"0","msg_cd":"OPSP0001","msg1":"UNSUBSCRIBE SUCCESS"}}"#;
+        assert_eq!(
+            parse_control(unsubscribed).unwrap().unwrap().tr_type(),
+            Some("2")
+        );
+        assert!(ping.error().is_none());
+
+        let fail = r#"{"header":{"tr_id":"H0STASP0","tr_key":"005930","encrypt":"N"},"body":{"rt_cd":"1","msg_cd":"OPSP0002","msg1":"ALREADY IN SUBSCRIBE"}}"#;
+        assert!(matches!(
+            parse_control(fail).unwrap().unwrap().error(),
+            Some(Error::Api { .. })
+        ));
+        assert_eq!(parse_control("0|H0STCNT0|001|005930").unwrap(), None);
+    }
+
+    #[test]
+    fn test_execution_notice() {
+        let key = "0123456789abcdef0123456789abcdef";
+        let iv = "0123456789abcdef";
+        let fields = [
+            "hts-id",
+            "5006725201",
+            "0000117057",
+            "",
+            "02",
+            "0",
+            "00",
+            "0",
+            "005930",
+            "10",
+            "70000",
+            "093001",
+            "0",
+            "2",
+            "2",
+            "91252",
+            "10",
+            "홍길동",
+            "삼성전자",
+            "10",
+            "",
+            "삼성전자",
+            "70000",
+        ];
+        let plain = fields.join("^");
+        let encrypted = symm::encrypt(
+            symm::Cipher::aes_256_cbc(),
+            key.as_bytes(),
+            Some(iv.as_bytes()),
+            plain.as_bytes(),
+        )
+        .unwrap();
+
+        let cipher = PayloadCipher::new(key, iv);
+        let payload = cipher.decrypt(&base64::encode(encrypted)).unwrap();
+        assert_eq!(payload, plain);
+
+        let notices = parse_records::<ExecutionNotice>(&payload, 1).unwrap();
+        let notice = &notices[0];
+        assert_eq!(notice.order_no, "0000117057");
+        assert_eq!(notice.side, OrderSide::Buy);
+        assert_eq!(notice.order_type, Some(OrderType::Limit));
+        assert_eq!(notice.filled_quantity, 10);
+        assert_eq!(notice.filled_price, 70000);
+        assert_eq!(notice.name, "삼성전자");
+        assert!(notice.filled);
+        assert!(!notice.rejected);
+
+        assert!(matches!(
+            PayloadCipher::new(key, "fedcba9876543210").decrypt("not base64!"),
+            Err(Error::Parse(_))
+        ));
+    }
+}
@@ -11,11 +11,23 @@ pub struct KisRequest {
     pub parameters: HashMap<String, String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RequestType {
     GET,
     POST,
     POSTTOKEN,
+    POSTREVOKE,
+    POSTAPPROVAL,
+}
+
+impl RequestType {
+    /// OAuth 요청은 authorization header 없이 body 로 인증
+    pub fn is_oauth(&self) -> bool {
+        matches!(
+            self,
+            RequestType::POSTTOKEN | RequestType::POSTREVOKE | RequestType::POSTAPPROVAL
+        )
+    }
 }
 
 impl KisRequest {
@@ -85,6 +97,47 @@ impl KisRequest {
                     ("grant_type".to_string(), "client_credentials".to_string()),
                 ]);
 
+                Self {
+                    req_type,
+                    headers,
+                    parameters,
+                    url,
+                }
+            }
+            RequestType::POSTREVOKE => {
+                // This is for only revoke access-token, token is added by caller
+                let mut headers = HeaderMap::new();
+                headers.insert(
+                    header::CONTENT_TYPE,
+                    HeaderValue::from_static("application/json"),
+                );
+
+                let parameters: HashMap<String, String> = HashMap::from([
+                    ("appkey".to_string(), conf.get_apikey().to_string()),
+                    ("appsecret".to_string(), conf.get_secret().to_string()),
+                ]);
+
+                Self {
+                    req_type,
+                    headers,
+                    parameters,
+                    url,
+                }
+            }
+            RequestType::POSTAPPROVAL => {
+                // This is for only get websocket approval key
+                let mut headers = HeaderMap::new();
+                headers.insert(
+                    header::CONTENT_TYPE,
+                    HeaderValue::from_static("application/json"),
+                );
+
+                let parameters: HashMap<String, String> = HashMap::from([
+                    ("grant_type".to_string(), "client_credentials".to_string()),
+                    ("appkey".to_string(), conf.get_apikey().to_string()),
+                    ("secretkey".to_string(), conf.get_secret().to_string()),
+                ]);
+
                 Self {
                     req_type,
                     headers,
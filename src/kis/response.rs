@@ -0,0 +1,187 @@
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use super::{Error, KisResult};
+
+/// KIS 응답 공통 필드 (rt_cd: 성공 실패 여부, msg_cd: 응답코드, msg1: 응답메세지)
+#[derive(Debug, Deserialize)]
+pub struct ResponseEnvelope {
+    pub rt_cd: String,
+    #[serde(default)]
+    pub msg_cd: String,
+    #[serde(default)]
+    pub msg1: String,
+}
+
+impl ResponseEnvelope {
+    pub fn is_success(&self) -> bool {
+        self.rt_cd == "0"
+    }
+
+    pub fn into_error(self) -> Error {
+        Error::Api {
+            rt_cd: self.rt_cd,
+            msg_cd: self.msg_cd,
+            msg1: self.msg1.trim().to_string(),
+        }
+    }
+}
+
+/// 응답 body 와 연속조회 여부 (tr_cont header)
+#[derive(Debug, Clone)]
+pub struct KisResponse {
+    pub body: serde_json::Value,
+    /// F, M : 다음 데이터 있음, D, E : 마지막 데이터
+    pub tr_cont: String,
+}
+
+impl KisResponse {
+    pub fn has_next(&self) -> bool {
+        matches!(self.tr_cont.as_str(), "F" | "M")
+    }
+}
+
+/// 연속조회 응답을 합침 : 목록(output, output1) 은 이어 붙이고 합계(output2) 등 나머지는 마지막 page 값 사용
+pub fn merge_page(acc: &mut serde_json::Value, page: serde_json::Value) {
+    let (acc, page) = match (acc.as_object_mut(), page) {
+        (Some(acc), serde_json::Value::Object(page)) => (acc, page),
+        (_, page) => {
+            *acc = page;
+            return;
+        }
+    };
+    for (key, value) in page {
+        match (acc.get_mut(&key), value) {
+            (Some(serde_json::Value::Array(rows)), serde_json::Value::Array(more))
+                if key == "output" || key == "output1" =>
+            {
+                rows.extend(more)
+            }
+            (_, value) => {
+                acc.insert(key, value);
+            }
+        }
+    }
+}
+
+/// HTTP status 와 body 로부터 KIS 응답을 해석
+///
+/// OAuth, hashkey 처럼 rt_cd 가 없는 응답은 HTTP 200 이면 그대로 돌려준다.
+/// rt_cd 가 있는 응답은 "0" 이 아니면 `Error::Api` 로 변환한다.
+pub fn parse_response(status: StatusCode, body: &str) -> KisResult<serde_json::Value> {
+    let value: serde_json::Value = match serde_json::from_str(body) {
+        Ok(v) => v,
+        Err(e) if status == StatusCode::OK => return Err(e.into()),
+        Err(_) => {
+            return Err(Error::Http {
+                status,
+                body: body.to_string(),
+            })
+        }
+    };
+
+    // KIS 는 업무 에러를 HTTP 200 또는 500 과 함께 rt_cd 로 알려준다
+    if let Ok(envelope) = ResponseEnvelope::deserialize(&value) {
+        if !envelope.is_success() {
+            return Err(envelope.into_error());
+        }
+    }
+
+    if status != StatusCode::OK {
+        return Err(Error::Http {
+            status,
+            body: body.to_string(),
+        });
+    }
+
+    Ok(value)
+}
+
+/// 응답에서 `key` (output, output1, output2 ...) 필드를 꺼내 `T` 로 변환
+pub fn take_output<T: DeserializeOwned>(value: &mut serde_json::Value, key: &str) -> KisResult<T> {
+    let output = value
+        .get_mut(key)
+        .map(serde_json::Value::take)
+        .ok_or_else(|| Error::Parse(format!("no {key} in response")))?;
+    serde_json::from_value(output).map_err(|e| Error::Parse(format!("{key}: {e}")))
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+
+    #[test]
+    fn test_merge_page() {
+        let mut acc = serde_json::json!({"rt_cd": "0", "output1": [{"pdno": "005930"}], "output2": [{"dnca_tot_amt": "1"}], "ctx_area_nk100": "A"});
+        let page = serde_json::json!({"rt_cd": "0", "output1": [{"pdno": "000660"}], "output2": [{"dnca_tot_amt": "2"}], "ctx_area_nk100": "B"});
+        merge_page(&mut acc, page);
+
+        assert_eq!(acc["output1"].as_array().unwrap().len(), 2);
+        assert_eq!(acc["output2"], serde_json::json!([{"dnca_tot_amt": "2"}]));
+        assert_eq!(acc["ctx_area_nk100"], "B");
+
+        let mut acc = serde_json::Value::Null;
+        merge_page(&mut acc, serde_json::json!({"output": []}));
+        assert!(acc["output"].is_array());
+    }
+
+    #[test]
+    fn test_parse_response_success() {
+        let body = r#"{"rt_cd":"0","msg_cd":"MCA00000","msg1":"정상처리 되었습니다.","output":{"stck_prpr":"70000"}}"#;
+        let v = parse_response(StatusCode::OK, body).unwrap();
+        assert_eq!(v["output"]["stck_prpr"], "70000");
+    }
+
+    #[test]
+    fn test_parse_response_without_envelope() {
+        let body = r#"{"HASH":"abcdef"}"#;
+        let v = parse_response(StatusCode::OK, body).unwrap();
+        assert_eq!(v["HASH"], "abcdef");
+    }
+
+    #[test]
+    fn test_parse_response_business_error() {
+        let body =
+            r#"{"rt_cd":"1","msg_cd":"APBK0952","msg1":"주문가능금액을 초과 했습니다     "}"#;
+        match parse_response(StatusCode::OK, body) {
+            Err(Error::Api {
+                rt_cd,
+                msg_cd,
+                msg1,
+            }) => {
+                assert_eq!(rt_cd, "1");
+                assert_eq!(msg_cd, "APBK0952");
+                assert_eq!(msg1, "주문가능금액을 초과 했습니다");
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_response_business_error_with_http_error() {
+        let body = r#"{"rt_cd":"1","msg_cd":"EGW00201","msg1":"초당 거래건수를 초과하였습니다."}"#;
+        let res = parse_response(StatusCode::INTERNAL_SERVER_ERROR, body);
+        assert!(matches!(res, Err(Error::Api { msg_cd, .. }) if msg_cd == "EGW00201"));
+    }
+
+    #[test]
+    fn test_take_output() {
+        let mut v = serde_json::json!({"rt_cd":"0","output":["a","b"]});
+        let out: Vec<String> = take_output(&mut v, "output").unwrap();
+        assert_eq!(out, vec!["a", "b"]);
+        assert!(take_output::<Vec<String>>(&mut v, "output1").is_err());
+    }
+
+    #[test]
+    fn test_parse_response_http_error() {
+        let res = parse_response(StatusCode::BAD_GATEWAY, "<html>Bad Gateway</html>");
+        assert!(matches!(
+            res,
+            Err(Error::Http {
+                status: StatusCode::BAD_GATEWAY,
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,364 @@
+//! 일시적인 실패에 대한 재시도 정책
+//!
+//! 조회(GET) 요청만 자동으로 재시도한다. 주문은 중복 체결 위험이 있으므로
+//! `order_stock_with_retry` 처럼 주문 조회로 중복 여부를 확인하는 경우에만 재시도한다.
+
+use std::time::Duration;
+
+use rand::Rng;
+use reqwest::StatusCode;
+
+use super::order::{OrderAck, OrderRequest};
+use super::trading::{Execution, ExecutionQuery};
+use super::{Error, KisResult};
+
+/// 초당 거래건수를 초과하였습니다.
+pub const RATE_LIMIT_MSG_CD: &str = "EGW00201";
+
+/// 재시도 횟수와 exponential backoff 설정
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// `max_retries` : 첫 요청 이후 재시도 횟수, n 번째 재시도는 최대 `base_delay * 2^(n-1)` 대기
+    pub fn new(max_retries: u32, base_delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            max_retries,
+            base_delay,
+            max_delay,
+        }
+    }
+
+    /// 재시도 하지 않음
+    pub fn none() -> Self {
+        Self::new(0, Duration::ZERO, Duration::ZERO)
+    }
+
+    pub fn get_max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    /// `retry` 번째 (1 부터) 재시도 전 대기 시간, [0, backoff] 사이의 full jitter
+    pub fn delay(&self, retry: u32) -> Duration {
+        let backoff = self
+            .base_delay
+            .saturating_mul(1 << retry.saturating_sub(1).min(16))
+            .min(self.max_delay);
+        if backoff.is_zero() {
+            return backoff;
+        }
+        rand::thread_rng().gen_range(Duration::ZERO..=backoff)
+    }
+
+    /// `retry` 번째 재시도를 해야 하면 대기 시간, 아니면 `None`
+    pub fn next_delay(&self, retry: u32, err: &Error) -> Option<Duration> {
+        if retry > self.max_retries || !is_transient(err) {
+            return None;
+        }
+        Some(self.delay(retry))
+    }
+}
+
+impl Default for RetryPolicy {
+    /// 3회, 200ms 부터 최대 5초
+    fn default() -> Self {
+        Self::new(3, Duration::from_millis(200), Duration::from_secs(5))
+    }
+}
+
+/// 한 요청의 재시도 횟수
+pub(crate) struct Retry<'a> {
+    policy: &'a RetryPolicy,
+    retry: u32,
+}
+
+impl<'a> Retry<'a> {
+    pub fn new(policy: &'a RetryPolicy) -> Self {
+        Self { policy, retry: 0 }
+    }
+
+    /// 요청 실패 후 다시 보내기 전 대기 시간, 재시도하지 않으면 `err`
+    pub fn on_error(&mut self, err: Error) -> KisResult<Duration> {
+        self.retry += 1;
+        self.policy.next_delay(self.retry, &err).ok_or(err)
+    }
+}
+
+/// 연결 실패, 타임아웃, 5xx, 호출 한도 초과
+pub fn is_transient(err: &Error) -> bool {
+    match err {
+        Error::Transport(e) => e.is_connect() || e.is_timeout(),
+        Error::Http { status, .. } => {
+            status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+        }
+        Error::Api { msg_cd, .. } => msg_cd == RATE_LIMIT_MSG_CD,
+        _ => false,
+    }
+}
+
+/// 주식일별주문체결조회 결과에서 `order` 와 같은 조건의 신규 주문 (정정/취소 제외)
+///
+/// 종목, 매수/매도, 주문구분, 수량, 가격만 비교한다. 같은 시각에 다른 전략이나 thread 가 낸
+/// 같은 조건의 주문도 찾을 수 있고, 현금/신용 (`Funding`) 은 구분하지 않는다.
+pub(crate) fn find_orders(executions: &[Execution], order: &OrderRequest) -> Vec<OrderAck> {
+    executions
+        .iter()
+        .filter(|e| {
+            e.ticker == order.get_ticker()
+                && e.side == order.get_side()
+                && e.order_type == Some(order.get_order_type())
+                && e.quantity == order.get_quantity() as u64
+                && e.price == order.get_price() as i64
+                && e.original_order_no.trim().is_empty()
+        })
+        .map(Execution::ack)
+        .collect()
+}
+
+/// 주문 실패 후 당일 주문 조회 전 최소 대기 시간, 접수된 주문이 조회에 나타날 때까지의 여유
+pub const ORDER_SETTLE_DELAY: Duration = Duration::from_millis(300);
+
+/// 주문 실패 후 접수 여부를 확인하기 전 상태
+pub(crate) struct PendingOrder {
+    /// 당일 주문 조회 전 대기 시간
+    pub wait: Duration,
+    err: Error,
+    retrying: bool,
+}
+
+/// `order_stock_with_retry` 의 재시도와 중복 주문 확인, 주문 전에 있던 같은 조건의 주문을 기억해 둠
+pub(crate) struct OrderRetry<'a> {
+    order: &'a OrderRequest,
+    known: Vec<OrderAck>,
+    policy: &'a RetryPolicy,
+    retry: u32,
+}
+
+impl<'a> OrderRetry<'a> {
+    /// 주문 전후로 조회할 당일 주문
+    pub fn history(order: &OrderRequest) -> ExecutionQuery {
+        ExecutionQuery::today()
+            .ticker(order.get_ticker())
+            .side(order.get_side())
+    }
+
+    pub fn new(order: &'a OrderRequest, policy: &'a RetryPolicy, executions: &[Execution]) -> Self {
+        Self {
+            order,
+            known: find_orders(executions, order),
+            policy,
+            retry: 0,
+        }
+    }
+
+    /// 주문 전에는 없던 같은 조건의 주문
+    pub fn placed(&self, executions: &[Execution]) -> Option<OrderAck> {
+        find_orders(executions, self.order)
+            .into_iter()
+            .find(|ack| !self.known.iter().any(|k| k.order_no == ack.order_no))
+    }
+
+    /// 주문 실패, `PendingOrder::wait` 동안 기다린 뒤 당일 주문을 조회해 `resolve` 로 넘김
+    ///
+    /// backoff 가 짧아도 `ORDER_SETTLE_DELAY` 이상 기다린다.
+    /// 업무 에러 (`Error::Api`) 는 접수되지 않았으므로 조회와 재시도 없이 에러.
+    pub fn on_failure(&mut self, err: Error) -> KisResult<PendingOrder> {
+        if matches!(err, Error::Api { .. }) && !is_transient(&err) {
+            return Err(err);
+        }
+        self.retry += 1;
+        let delay = self.policy.next_delay(self.retry, &err);
+        Ok(PendingOrder {
+            wait: delay.unwrap_or_default().max(ORDER_SETTLE_DELAY),
+            err,
+            retrying: delay.is_some(),
+        })
+    }
+
+    /// 주문 실패 후 당일 주문 조회 결과로 다음 동작 결정
+    ///
+    /// 접수된 주문이 보이면 `Some`, 다시 보내야 하면 `None`, 재시도하지 않으면 주문 에러.
+    /// 조회가 실패하면 재시도 중에는 조회 에러, 아니면 주문 에러.
+    pub fn resolve(
+        &self,
+        executions: KisResult<Vec<Execution>>,
+        pending: PendingOrder,
+    ) -> KisResult<Option<OrderAck>> {
+        match executions {
+            Ok(executions) => match self.placed(&executions) {
+                Some(ack) => Ok(Some(ack)),
+                None if pending.retrying => Ok(None),
+                None => Err(pending.err),
+            },
+            Err(e) if pending.retrying => Err(e),
+            Err(_) => Err(pending.err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+
+    #[test]
+    fn test_delay_backoff() {
+        let policy = RetryPolicy::new(5, Duration::from_millis(100), Duration::from_millis(300));
+        for _ in 0..20 {
+            assert!(policy.delay(1) <= Duration::from_millis(100));
+            assert!(policy.delay(2) <= Duration::from_millis(200));
+            // max_delay 로 제한
+            assert!(policy.delay(5) <= Duration::from_millis(300));
+        }
+        assert_eq!(RetryPolicy::none().delay(1), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_next_delay() {
+        let policy = RetryPolicy::default();
+        let rate_limit = Error::Api {
+            rt_cd: "1".to_string(),
+            msg_cd: RATE_LIMIT_MSG_CD.to_string(),
+            msg1: "초당 거래건수를 초과하였습니다.".to_string(),
+        };
+        let server_error = Error::Http {
+            status: StatusCode::BAD_GATEWAY,
+            body: String::new(),
+        };
+        let business_error = Error::Api {
+            rt_cd: "1".to_string(),
+            msg_cd: "APBK0919".to_string(),
+            msg1: "주문가능금액을 초과 했습니다".to_string(),
+        };
+
+        assert!(policy.next_delay(1, &rate_limit).is_some());
+        assert!(policy.next_delay(3, &server_error).is_some());
+        assert!(policy.next_delay(4, &server_error).is_none());
+        assert!(policy.next_delay(1, &business_error).is_none());
+        assert!(RetryPolicy::none().next_delay(1, &rate_limit).is_none());
+    }
+
+    #[test]
+    fn test_find_orders() {
+        let order = OrderRequest::buy("005930")
+            .quantity(10)
+            .price(70000)
+            .build()
+            .unwrap();
+        let row = |side: &str, odno: &str, orgn_odno: &str| serde_json::json!({"ord_dt": "20240105", "pdno": "005930", "sll_buy_dvsn_cd": side, "ord_dvsn_cd": "00", "ord_qty": "10", "ord_unpr": "70000", "orgn_odno": orgn_odno, "ord_gno_brno": "91252", "odno": odno, "ord_tmd": "121052", "tot_ccld_qty": "0"});
+        let executions: Vec<Execution> = serde_json::from_value(serde_json::json!([
+            row("02", "0000117057", ""),
+            row("01", "0000117058", ""),
+            row("02", "0000117059", "0000117057"),
+        ]))
+        .unwrap();
+
+        let found = find_orders(&executions, &order);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].order_no, "0000117057");
+        assert!(find_orders(&[], &order).is_empty());
+    }
+
+    #[test]
+    fn test_order_dedup() {
+        let order = OrderRequest::buy("005930")
+            .quantity(10)
+            .price(70000)
+            .build()
+            .unwrap();
+        let row = |odno: &str| serde_json::json!({"ord_dt": "20240105", "pdno": "005930", "sll_buy_dvsn_cd": "02", "ord_dvsn_cd": "00", "ord_qty": "10", "ord_unpr": "70000", "orgn_odno": "", "ord_gno_brno": "91252", "odno": odno, "ord_tmd": "121052", "tot_ccld_qty": "0"});
+        let before: Vec<Execution> =
+            serde_json::from_value(serde_json::json!([row("0000117056")])).unwrap();
+        let after: Vec<Execution> =
+            serde_json::from_value(serde_json::json!([row("0000117056"), row("0000117057")]))
+                .unwrap();
+        let err = || Error::Http {
+            status: StatusCode::BAD_GATEWAY,
+            body: String::new(),
+        };
+        let policy = RetryPolicy::none();
+        let mut retry = OrderRetry::new(&order, &policy, &before);
+
+        assert_eq!(retry.placed(&before), None);
+        let pending = retry.on_failure(err()).unwrap();
+        let ack = retry.resolve(Ok(after), pending).unwrap().unwrap();
+        assert_eq!(ack.order_no, "0000117057");
+        let pending = retry.on_failure(err()).unwrap();
+        assert!(matches!(
+            retry.resolve(Ok(before.clone()), pending),
+            Err(Error::Http { .. })
+        ));
+        // 마지막 조회가 실패하면 주문 에러
+        let pending = retry.on_failure(err()).unwrap();
+        assert!(matches!(
+            retry.resolve(Err(Error::Config("inquiry".to_string())), pending),
+            Err(Error::Http { .. })
+        ));
+
+        // 재시도 중에는 다시 보내고, 조회가 실패하면 조회 에러
+        let policy = RetryPolicy::new(2, Duration::ZERO, Duration::ZERO);
+        let mut retry = OrderRetry::new(&order, &policy, &before);
+        let pending = retry.on_failure(err()).unwrap();
+        assert_eq!(retry.resolve(Ok(before), pending).unwrap(), None);
+        let pending = retry.on_failure(err()).unwrap();
+        assert!(matches!(
+            retry.resolve(Err(Error::Config("inquiry".to_string())), pending),
+            Err(Error::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_order_failure() {
+        let order = OrderRequest::buy("005930")
+            .quantity(10)
+            .price(70000)
+            .build()
+            .unwrap();
+        let policy = RetryPolicy::new(1, Duration::from_millis(1), Duration::from_millis(1));
+        let mut retry = OrderRetry::new(&order, &policy, &[]);
+        let server_error = || Error::Http {
+            status: StatusCode::BAD_GATEWAY,
+            body: String::new(),
+        };
+        let business_error = Error::Api {
+            rt_cd: "1".to_string(),
+            msg_cd: "APBK0919".to_string(),
+            msg1: "주문가능금액을 초과 했습니다".to_string(),
+        };
+
+        // backoff 가 짧아도 접수 여부는 기다렸다가 조회
+        let pending = retry.on_failure(server_error()).unwrap();
+        assert_eq!(pending.wait, ORDER_SETTLE_DELAY);
+        assert!(pending.retrying);
+        let pending = retry.on_failure(server_error()).unwrap();
+        assert_eq!(pending.wait, ORDER_SETTLE_DELAY);
+        assert!(!pending.retrying);
+        assert!(matches!(
+            retry.on_failure(business_error),
+            Err(Error::Api { .. })
+        ));
+    }
+
+    #[test]
+    fn test_retry() {
+        let policy = RetryPolicy::new(1, Duration::ZERO, Duration::ZERO);
+        let mut retry = Retry::new(&policy);
+        let server_error = || Error::Http {
+            status: StatusCode::BAD_GATEWAY,
+            body: String::new(),
+        };
+
+        assert_eq!(retry.on_error(server_error()).unwrap(), Duration::ZERO);
+        assert!(matches!(
+            retry.on_error(server_error()),
+            Err(Error::Http { .. })
+        ));
+        assert!(matches!(
+            Retry::new(&policy).on_error(Error::Config("config".to_string())),
+            Err(Error::Config(_))
+        ));
+    }
+}
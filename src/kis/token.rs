@@ -0,0 +1,143 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{Error, KisResult};
+
+/// 만료 전 미리 재발급 하기 위한 여유 시간
+pub const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(10 * 60);
+
+/// 실시간 (웹소켓) 접속키 유효시간 (24시간)
+pub const APPROVAL_KEY_EXPIRES_IN: u64 = 24 * 60 * 60;
+
+/// 만료 시각과 함께 보관하는 access token
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AccessToken {
+    token: String,
+    /// 만료 시각 (unix timestamp, 초)
+    expires_at: u64,
+}
+
+impl AccessToken {
+    /// `expires_in` : 발급 시점부터 유효 시간 (초)
+    pub fn new(token: &str, expires_in: u64) -> Self {
+        Self {
+            token: token.to_string(),
+            expires_at: now() + expires_in,
+        }
+    }
+
+    pub fn get_token(&self) -> &str {
+        &self.token
+    }
+
+    pub fn expires_at(&self) -> u64 {
+        self.expires_at
+    }
+
+    /// 재발급 여유 시간(`TOKEN_REFRESH_MARGIN`) 을 고려한 유효 여부
+    pub fn is_valid(&self) -> bool {
+        self.is_valid_for(TOKEN_REFRESH_MARGIN)
+    }
+
+    /// `margin` 이후에도 만료되지 않는지 여부
+    pub fn is_valid_for(&self, margin: Duration) -> bool {
+        !self.token.is_empty() && now() + margin.as_secs() < self.expires_at
+    }
+
+    /// 접근토큰발급 응답 (access_token, expires_in)
+    pub fn from_token_response(val: &Value) -> KisResult<Self> {
+        let token = val["access_token"]
+            .as_str()
+            .ok_or_else(|| Error::Auth(format!("no access_token in token response: {val}")))?;
+        let expires_in = val["expires_in"]
+            .as_u64()
+            .ok_or_else(|| Error::Auth(format!("no expires_in in token response: {val}")))?;
+        Ok(Self::new(token, expires_in))
+    }
+
+    /// 실시간 (웹소켓) 접속키 발급 응답 (approval_key)
+    pub fn from_approval_response(val: &Value) -> KisResult<Self> {
+        let key = val["approval_key"]
+            .as_str()
+            .ok_or_else(|| Error::Auth(format!("no approval_key in response: {val}")))?;
+        Ok(Self::new(key, APPROVAL_KEY_EXPIRES_IN))
+    }
+
+    /// 캐시 파일의 token 이 유효하면 사용, 아니면 빈 token
+    pub fn load_valid(path: Option<PathBuf>) -> Self {
+        path.and_then(|path| Self::load(&path).ok().flatten())
+            .filter(Self::is_valid)
+            .unwrap_or_default()
+    }
+
+    /// 캐시 파일에서 token 읽기, 파일이 없으면 `None`
+    pub fn load(path: &Path) -> KisResult<Option<Self>> {
+        match fs::read_to_string(path) {
+            Ok(data) => Ok(Some(serde_json::from_str(&data)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> KisResult<()> {
+        let data = serde_json::to_string_pretty(self)?;
+        fs::write(path, data).map_err(|e| Error::Config(format!("{}: {e}", path.display())))
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+
+    #[test]
+    fn test_token_validity() {
+        assert!(!AccessToken::default().is_valid());
+        assert!(AccessToken::new("token", 86400).is_valid());
+        // 만료까지 여유 시간보다 적게 남으면 재발급 대상
+        assert!(!AccessToken::new("token", 60).is_valid());
+        assert!(AccessToken::new("token", 60).is_valid_for(Duration::ZERO));
+        assert!(!AccessToken::new("", 86400).is_valid());
+    }
+
+    #[test]
+    fn test_token_save_load() {
+        let path = std::env::temp_dir().join(format!("kis_token_{}.json", std::process::id()));
+        let token = AccessToken::new("token", 86400);
+        token.save(&path).unwrap();
+
+        let loaded = AccessToken::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, Some(token));
+        assert_eq!(AccessToken::load(&path).unwrap(), None);
+        assert_eq!(AccessToken::load_valid(Some(path)), AccessToken::default());
+    }
+
+    #[test]
+    fn test_token_response() {
+        let v = serde_json::json!({"access_token": "token", "token_type": "Bearer", "expires_in": 86400});
+        let token = AccessToken::from_token_response(&v).unwrap();
+        assert_eq!(token.get_token(), "token");
+        assert!(token.is_valid());
+
+        let v = serde_json::json!({"error_description": "failed"});
+        assert!(matches!(
+            AccessToken::from_token_response(&v),
+            Err(Error::Auth(_))
+        ));
+        assert!(matches!(
+            AccessToken::from_approval_response(&v),
+            Err(Error::Auth(_))
+        ));
+    }
+}
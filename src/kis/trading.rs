@@ -0,0 +1,812 @@
+//! 국내주식주문 조회 요청/응답 타입
+
+use std::collections::HashMap;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+use super::de;
+use super::endpoint::kst_today;
+use super::order::{validate_ticker, OrderAck, OrderSide, OrderType};
+use super::response::take_output;
+use super::{Error, KisResult};
+
+/// 주식잔고조회 output1 : 보유 종목
+///
+/// 당일 전량 매도한 종목은 보유수량 0 으로 포함된다.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Holding {
+    /// 종목번호
+    #[serde(rename = "pdno")]
+    pub ticker: String,
+    /// 종목명
+    #[serde(rename = "prdt_name", default)]
+    pub name: String,
+    /// 보유수량
+    #[serde(rename = "hldg_qty", deserialize_with = "de::number")]
+    pub quantity: u64,
+    /// 주문가능수량
+    #[serde(rename = "ord_psbl_qty", deserialize_with = "de::number")]
+    pub orderable_quantity: u64,
+    /// 매입평균가격
+    #[serde(rename = "pchs_avg_pric", deserialize_with = "de::number")]
+    pub avg_price: f64,
+    /// 매입금액
+    #[serde(rename = "pchs_amt", default, deserialize_with = "de::number")]
+    pub purchase_amount: i64,
+    /// 현재가
+    #[serde(rename = "prpr", deserialize_with = "de::number")]
+    pub price: i64,
+    /// 평가금액
+    #[serde(rename = "evlu_amt", default, deserialize_with = "de::number")]
+    pub eval_amount: i64,
+    /// 평가손익금액
+    #[serde(rename = "evlu_pfls_amt", deserialize_with = "de::number")]
+    pub eval_pnl: i64,
+    /// 평가손익율
+    #[serde(rename = "evlu_pfls_rt", deserialize_with = "de::number")]
+    pub pnl_rate: f64,
+    /// 모델링되지 않은 나머지 필드
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// 주식잔고조회 output2 : 계좌 합계
+#[derive(Debug, Clone, Deserialize)]
+pub struct AccountSummary {
+    /// 예수금총금액
+    #[serde(rename = "dnca_tot_amt", deserialize_with = "de::number")]
+    pub deposit: i64,
+    /// 익일정산금액 (D+1 예수금)
+    #[serde(rename = "nxdy_excc_amt", default, deserialize_with = "de::number")]
+    pub deposit_d1: i64,
+    /// 가수도정산금액 (D+2 예수금)
+    #[serde(rename = "prvs_rcdl_excc_amt", deserialize_with = "de::number")]
+    pub deposit_d2: i64,
+    /// 유가평가금액
+    #[serde(rename = "scts_evlu_amt", default, deserialize_with = "de::number")]
+    pub stock_eval_amount: i64,
+    /// 총평가금액 (유가평가금액 + D+2 예수금)
+    #[serde(rename = "tot_evlu_amt", deserialize_with = "de::number")]
+    pub total_eval_amount: i64,
+    /// 순자산금액
+    #[serde(rename = "nass_amt", deserialize_with = "de::number")]
+    pub net_asset: i64,
+    /// 평가손익합계금액
+    #[serde(
+        rename = "evlu_pfls_smtl_amt",
+        default,
+        deserialize_with = "de::number"
+    )]
+    pub eval_pnl: i64,
+    /// 모델링되지 않은 나머지 필드
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// 주식잔고조회 : 모든 page 의 보유 종목과 계좌 합계
+#[derive(Debug, Clone)]
+pub struct AccountBalance {
+    pub holdings: Vec<Holding>,
+    pub summary: AccountSummary,
+}
+
+impl AccountBalance {
+    /// 연속조회를 합친 응답, 합계는 마지막 page 의 output2 사용
+    pub(crate) fn from_response(mut v: Value) -> KisResult<Self> {
+        let holdings = take_output(&mut v, "output1")?;
+        let summary: Vec<AccountSummary> = take_output(&mut v, "output2")?;
+        let summary = summary
+            .into_iter()
+            .last()
+            .ok_or_else(|| Error::Parse("empty output2 in balance response".to_string()))?;
+
+        Ok(AccountBalance { holdings, summary })
+    }
+}
+
+/// 매수가능조회 output
+#[derive(Debug, Clone, Deserialize)]
+pub struct BuyingPower {
+    /// 주문가능현금
+    #[serde(rename = "ord_psbl_cash", deserialize_with = "de::number")]
+    pub cash: i64,
+    /// 가능수량계산단가 (시장가 주문은 조회 시점 가격)
+    #[serde(
+        rename = "psbl_qty_calc_unpr",
+        default,
+        deserialize_with = "de::number"
+    )]
+    pub calc_price: i64,
+    /// 미수없는매수금액
+    #[serde(rename = "nrcvb_buy_amt", deserialize_with = "de::number")]
+    pub cash_amount: i64,
+    /// 미수없는매수수량
+    #[serde(rename = "nrcvb_buy_qty", deserialize_with = "de::number")]
+    pub cash_quantity: u64,
+    /// 최대매수금액 (미수 포함)
+    #[serde(rename = "max_buy_amt", deserialize_with = "de::number")]
+    pub max_amount: i64,
+    /// 최대매수수량 (미수 포함)
+    #[serde(rename = "max_buy_qty", deserialize_with = "de::number")]
+    pub max_quantity: u64,
+    /// 모델링되지 않은 나머지 필드
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl BuyingPower {
+    /// 원하는 수량을 미수 없이 살 수 있는 수량으로 제한
+    pub fn bound_quantity(&self, quantity: u64) -> u64 {
+        quantity.min(self.cash_quantity)
+    }
+}
+
+/// 체결구분 (CCLD_DVSN)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FillFilter {
+    /// 00 : 전체
+    #[default]
+    All,
+    /// 01 : 체결
+    Filled,
+    /// 02 : 미체결
+    Unfilled,
+}
+
+impl FillFilter {
+    pub fn code(&self) -> &'static str {
+        match self {
+            FillFilter::All => "00",
+            FillFilter::Filled => "01",
+            FillFilter::Unfilled => "02",
+        }
+    }
+}
+
+/// 주식일별주문체결조회 조건, 조회 기간은 3개월 이내
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionQuery {
+    start_date: String,
+    end_date: String,
+    side: Option<OrderSide>,
+    ticker: String,
+    fill: FillFilter,
+}
+
+impl ExecutionQuery {
+    /// `start_date`, `end_date` : YYYYMMDD
+    pub fn new(start_date: &str, end_date: &str) -> Self {
+        Self {
+            start_date: start_date.to_string(),
+            end_date: end_date.to_string(),
+            side: None,
+            ticker: String::new(),
+            fill: FillFilter::All,
+        }
+    }
+
+    /// 한국 시간 기준 당일
+    pub fn today() -> Self {
+        let today = kst_today();
+        Self::new(&today, &today)
+    }
+
+    /// 매수 또는 매도만 조회, 기본값은 전체
+    pub fn side(mut self, side: OrderSide) -> Self {
+        self.side = Some(side);
+        self
+    }
+
+    /// 종목 하나만 조회, 기본값은 전체
+    pub fn ticker(mut self, ticker: &str) -> Self {
+        self.ticker = ticker.to_string();
+        self
+    }
+
+    pub fn fill(mut self, fill: FillFilter) -> Self {
+        self.fill = fill;
+        self
+    }
+
+    pub fn get_start_date(&self) -> &str {
+        &self.start_date
+    }
+
+    pub fn get_end_date(&self) -> &str {
+        &self.end_date
+    }
+
+    pub fn get_side(&self) -> Option<OrderSide> {
+        self.side
+    }
+
+    pub fn get_ticker(&self) -> &str {
+        &self.ticker
+    }
+
+    pub fn get_fill(&self) -> FillFilter {
+        self.fill
+    }
+
+    /// 날짜 형식과 순서, 종목코드 검증
+    pub(crate) fn validate(&self) -> KisResult<()> {
+        validate_period(&self.start_date, &self.end_date, &self.ticker)
+    }
+
+    /// 3개월 이전 기간 조회 여부, 3개월 이내와 이전은 tr_id 가 달라 기간이 걸치면 오류
+    pub(crate) fn is_before_3_months(&self) -> KisResult<bool> {
+        self.is_before_3_months_from(&kst_today())
+    }
+
+    fn is_before_3_months_from(&self, today: &str) -> KisResult<bool> {
+        let cutoff = months_ago(today, 3);
+        if self.start_date >= cutoff {
+            Ok(false)
+        } else if self.end_date < cutoff {
+            Ok(true)
+        } else {
+            Err(Error::InvalidRequest(format!(
+                "period {}~{} spans the 3 month boundary {cutoff}, split it",
+                self.start_date, self.end_date
+            )))
+        }
+    }
+}
+
+/// `months` 개월 전 날짜 (YYYYMMDD), 그 달에 없는 날은 말일로
+fn months_ago(date: &str, months: u32) -> String {
+    let year: u32 = date[..4].parse().unwrap_or_default();
+    let month: u32 = date[4..6].parse().unwrap_or_default();
+    let day: u32 = date[6..].parse().unwrap_or_default();
+
+    let total = (year * 12 + month - 1).saturating_sub(months);
+    let (year, month) = (total / 12, total % 12 + 1);
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let last_day = match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    };
+    format!("{year:04}{month:02}{:02}", day.min(last_day))
+}
+
+/// 조회 기간과 종목 (빈 문자열이면 전체) 검증
+pub(crate) fn validate_period(start_date: &str, end_date: &str, ticker: &str) -> KisResult<()> {
+    validate_date(start_date)?;
+    validate_date(end_date)?;
+    if start_date > end_date {
+        return Err(Error::InvalidRequest(format!(
+            "start date {start_date} is after end date {end_date}"
+        )));
+    }
+    if !ticker.is_empty() {
+        validate_ticker(ticker)?;
+    }
+    Ok(())
+}
+
+/// 조회 날짜 (YYYYMMDD)
+pub(crate) fn validate_date(date: &str) -> KisResult<()> {
+    if date.len() != 8 || !date.chars().all(|c| c.is_ascii_digit()) {
+        return Err(Error::InvalidRequest(format!("invalid date: {date:?}")));
+    }
+    Ok(())
+}
+
+/// 주식일별주문체결조회 output1 : 주문 하나의 체결 현황
+#[derive(Debug, Clone, Deserialize)]
+pub struct Execution {
+    /// 주문일자 (YYYYMMDD)
+    #[serde(rename = "ord_dt")]
+    pub order_date: String,
+    /// 주문채번지점번호 (한국거래소전송주문조직번호)
+    #[serde(rename = "ord_gno_brno")]
+    pub krx_orgno: String,
+    /// 주문번호
+    #[serde(rename = "odno")]
+    pub order_no: String,
+    /// 원주문번호, 정정/취소 주문에만 있음
+    #[serde(rename = "orgn_odno", default)]
+    pub original_order_no: String,
+    /// 매도매수구분코드
+    #[serde(rename = "sll_buy_dvsn_cd", deserialize_with = "order_side")]
+    pub side: OrderSide,
+    /// 주문구분코드, 알 수 없는 코드는 `None`
+    #[serde(rename = "ord_dvsn_cd", default, deserialize_with = "order_type")]
+    pub order_type: Option<OrderType>,
+    /// 종목번호
+    #[serde(rename = "pdno")]
+    pub ticker: String,
+    /// 종목명
+    #[serde(rename = "prdt_name", default)]
+    pub name: String,
+    /// 주문수량
+    #[serde(rename = "ord_qty", deserialize_with = "de::number")]
+    pub quantity: u64,
+    /// 주문단가
+    #[serde(rename = "ord_unpr", deserialize_with = "de::number")]
+    pub price: i64,
+    /// 주문시각 (HHMMSS)
+    #[serde(rename = "ord_tmd", default)]
+    pub order_time: String,
+    /// 총체결수량
+    #[serde(rename = "tot_ccld_qty", deserialize_with = "de::number")]
+    pub filled_quantity: u64,
+    /// 체결평균가
+    #[serde(rename = "avg_prvs", default, deserialize_with = "de::number")]
+    pub avg_price: f64,
+    /// 총체결금액
+    #[serde(rename = "tot_ccld_amt", default, deserialize_with = "de::number")]
+    pub filled_amount: i64,
+    /// 잔여수량
+    #[serde(rename = "rmn_qty", default, deserialize_with = "de::number")]
+    pub remaining_quantity: u64,
+    /// 취소확인수량
+    #[serde(rename = "cncl_cfrm_qty", default, deserialize_with = "de::number")]
+    pub cancelled_quantity: u64,
+    /// 거부수량
+    #[serde(rename = "rjct_qty", default, deserialize_with = "de::number")]
+    pub rejected_quantity: u64,
+    /// 취소여부
+    #[serde(rename = "cncl_yn", default, deserialize_with = "yes_no")]
+    pub cancelled: bool,
+    /// 모델링되지 않은 나머지 필드
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl Execution {
+    /// 정정/취소에 사용할 주문 정보
+    pub fn ack(&self) -> OrderAck {
+        OrderAck {
+            krx_orgno: self.krx_orgno.clone(),
+            order_no: self.order_no.clone(),
+            order_time: self.order_time.clone(),
+        }
+    }
+
+    /// 주문 수량 전부 체결
+    pub fn is_filled(&self) -> bool {
+        self.quantity > 0 && self.filled_quantity >= self.quantity
+    }
+}
+
+/// 주식예약주문조회 output : 예약주문 하나의 처리 현황
+#[derive(Debug, Clone, Deserialize)]
+pub struct Reservation {
+    /// 예약주문순번
+    #[serde(rename = "rsvn_ord_seq", deserialize_with = "de::number")]
+    pub seq: u64,
+    /// 예약주문주문일자 (YYYYMMDD)
+    #[serde(rename = "rsvn_ord_ord_dt", default)]
+    pub order_date: String,
+    /// 예약종료일자, 기간예약이 아니면 빈 문자열
+    #[serde(rename = "rsvn_end_dt", default)]
+    pub end_date: String,
+    /// 종목번호
+    #[serde(rename = "pdno")]
+    pub ticker: String,
+    /// 종목명
+    #[serde(rename = "kor_item_shtn_name", default)]
+    pub name: String,
+    /// 매도매수구분코드
+    #[serde(rename = "sll_buy_dvsn_cd", deserialize_with = "order_side")]
+    pub side: OrderSide,
+    /// 주문구분코드, 알 수 없는 코드는 `None`
+    #[serde(rename = "ord_dvsn_cd", default, deserialize_with = "order_type")]
+    pub order_type: Option<OrderType>,
+    /// 주문예약수량
+    #[serde(rename = "ord_rsvn_qty", deserialize_with = "de::number")]
+    pub quantity: u64,
+    /// 주문예약단가
+    #[serde(rename = "ord_rsvn_unpr", default, deserialize_with = "de::number")]
+    pub price: i64,
+    /// 전송된 주문의 주문번호, 전송 전에는 빈 문자열
+    #[serde(rename = "odno", default)]
+    pub order_no: String,
+    /// 총체결수량
+    #[serde(rename = "tot_ccld_qty", default, deserialize_with = "de::number")]
+    pub filled_quantity: u64,
+    /// 총체결금액
+    #[serde(rename = "tot_ccld_amt", default, deserialize_with = "de::number")]
+    pub filled_amount: i64,
+    /// 취소주문일자, 취소되지 않았으면 빈 문자열
+    #[serde(rename = "cncl_ord_dt", default)]
+    pub cancel_date: String,
+    /// 처리결과
+    #[serde(rename = "prcs_rslt", default)]
+    pub result: String,
+    /// 거부사유
+    #[serde(rename = "rjct_rson2", default)]
+    pub reject_reason: String,
+    /// 모델링되지 않은 나머지 필드
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl Reservation {
+    pub fn is_cancelled(&self) -> bool {
+        !self.cancel_date.trim().is_empty()
+    }
+}
+
+/// 기간별매매손익현황조회, 기간별손익일별합산조회 조건
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfitQuery {
+    start_date: String,
+    end_date: String,
+    ticker: String,
+}
+
+impl ProfitQuery {
+    /// `start_date`, `end_date` : YYYYMMDD
+    pub fn new(start_date: &str, end_date: &str) -> Self {
+        Self {
+            start_date: start_date.to_string(),
+            end_date: end_date.to_string(),
+            ticker: String::new(),
+        }
+    }
+
+    /// 한국 시간 기준 당일
+    pub fn today() -> Self {
+        let today = kst_today();
+        Self::new(&today, &today)
+    }
+
+    /// 종목 하나만 조회, 기본값은 전체
+    pub fn ticker(mut self, ticker: &str) -> Self {
+        self.ticker = ticker.to_string();
+        self
+    }
+
+    pub fn get_start_date(&self) -> &str {
+        &self.start_date
+    }
+
+    pub fn get_end_date(&self) -> &str {
+        &self.end_date
+    }
+
+    pub fn get_ticker(&self) -> &str {
+        &self.ticker
+    }
+
+    /// 날짜 형식과 순서, 종목코드 검증
+    pub(crate) fn validate(&self) -> KisResult<()> {
+        validate_period(&self.start_date, &self.end_date, &self.ticker)
+    }
+}
+
+/// 기간별매매손익현황조회 output1 : 매매일자별 종목 하나의 실현손익
+#[derive(Debug, Clone, Deserialize)]
+pub struct TradeProfit {
+    /// 매매일자 (YYYYMMDD)
+    #[serde(rename = "trad_dt")]
+    pub trade_date: String,
+    /// 종목번호
+    #[serde(rename = "pdno")]
+    pub ticker: String,
+    /// 종목명
+    #[serde(rename = "prdt_name", default)]
+    pub name: String,
+    /// 매수수량
+    #[serde(rename = "buy_qty", default, deserialize_with = "de::number")]
+    pub buy_quantity: u64,
+    /// 매수금액
+    #[serde(rename = "buy_amt", default, deserialize_with = "de::number")]
+    pub buy_amount: i64,
+    /// 매입단가
+    #[serde(rename = "pchs_unpr", default, deserialize_with = "de::number")]
+    pub buy_price: f64,
+    /// 매도수량
+    #[serde(rename = "sll_qty", default, deserialize_with = "de::number")]
+    pub sell_quantity: u64,
+    /// 매도금액
+    #[serde(rename = "sll_amt", default, deserialize_with = "de::number")]
+    pub sell_amount: i64,
+    /// 매도가격
+    #[serde(rename = "sll_pric", default, deserialize_with = "de::number")]
+    pub sell_price: f64,
+    /// 수수료
+    #[serde(rename = "fee", default, deserialize_with = "de::number")]
+    pub fee: i64,
+    /// 제세금
+    #[serde(rename = "tl_tax", default, deserialize_with = "de::number")]
+    pub tax: i64,
+    /// 대출이자
+    #[serde(rename = "loan_int", default, deserialize_with = "de::number")]
+    pub loan_interest: i64,
+    /// 실현손익 (수수료, 제세금 차감 후)
+    #[serde(rename = "rlzt_pfls", default, deserialize_with = "de::number")]
+    pub realized_profit: i64,
+    /// 손익률
+    #[serde(rename = "pfls_rt", default, deserialize_with = "de::number")]
+    pub profit_rate: f64,
+    /// 모델링되지 않은 나머지 필드
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// 기간별손익일별합산조회 output1 : 하루의 실현손익 합계
+#[derive(Debug, Clone, Deserialize)]
+pub struct DailyProfit {
+    /// 매매일자 (YYYYMMDD)
+    #[serde(rename = "trad_dt")]
+    pub trade_date: String,
+    /// 매수수량
+    #[serde(rename = "buy_qty1", default, deserialize_with = "de::number")]
+    pub buy_quantity: u64,
+    /// 매수금액
+    #[serde(rename = "buy_amt", default, deserialize_with = "de::number")]
+    pub buy_amount: i64,
+    /// 매도수량
+    #[serde(rename = "sll_qty1", default, deserialize_with = "de::number")]
+    pub sell_quantity: u64,
+    /// 매도금액
+    #[serde(rename = "sll_amt", default, deserialize_with = "de::number")]
+    pub sell_amount: i64,
+    /// 수수료
+    #[serde(rename = "fee", default, deserialize_with = "de::number")]
+    pub fee: i64,
+    /// 제세금
+    #[serde(rename = "tl_tax", default, deserialize_with = "de::number")]
+    pub tax: i64,
+    /// 대출이자
+    #[serde(rename = "loan_int", default, deserialize_with = "de::number")]
+    pub loan_interest: i64,
+    /// 실현손익
+    #[serde(rename = "rlzt_pfls", default, deserialize_with = "de::number")]
+    pub realized_profit: i64,
+    /// 손익률
+    #[serde(rename = "pfls_rt", default, deserialize_with = "de::number")]
+    pub profit_rate: f64,
+    /// 모델링되지 않은 나머지 필드
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// 기간별 손익 조회 output2 : 조회 기간 합계
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProfitSummary {
+    /// 매수수량합계
+    #[serde(
+        rename = "buy_qty_smtl",
+        alias = "buyqty_smtl",
+        default,
+        deserialize_with = "de::number"
+    )]
+    pub buy_quantity: u64,
+    /// 매수거래금액합계
+    #[serde(rename = "buy_tr_amt_smtl", default, deserialize_with = "de::number")]
+    pub buy_amount: i64,
+    /// 매수수수료합계
+    #[serde(rename = "buy_fee_smtl", default, deserialize_with = "de::number")]
+    pub buy_fee: i64,
+    /// 매수제세금합계
+    #[serde(rename = "buy_tax_smtl", default, deserialize_with = "de::number")]
+    pub buy_tax: i64,
+    /// 매도수량합계
+    #[serde(rename = "sll_qty_sum", default, deserialize_with = "de::number")]
+    pub sell_quantity: u64,
+    /// 매도거래금액합계
+    #[serde(rename = "sll_tr_amt_sum", default, deserialize_with = "de::number")]
+    pub sell_amount: i64,
+    /// 매도수수료합계
+    #[serde(rename = "sll_fee_smtl", default, deserialize_with = "de::number")]
+    pub sell_fee: i64,
+    /// 매도제세금합계
+    #[serde(rename = "sll_tltx_smtl", default, deserialize_with = "de::number")]
+    pub sell_tax: i64,
+    /// 총수수료
+    #[serde(rename = "tot_fee", default, deserialize_with = "de::number")]
+    pub fee: i64,
+    /// 총제세금
+    #[serde(rename = "tot_tltx", default, deserialize_with = "de::number")]
+    pub tax: i64,
+    /// 대출이자
+    #[serde(rename = "loan_int", default, deserialize_with = "de::number")]
+    pub loan_interest: i64,
+    /// 총실현손익
+    #[serde(rename = "tot_rlzt_pfls", default, deserialize_with = "de::number")]
+    pub realized_profit: i64,
+    /// 총수익률
+    #[serde(rename = "tot_pftrt", default, deserialize_with = "de::number")]
+    pub profit_rate: f64,
+    /// 모델링되지 않은 나머지 필드
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// 기간별 손익 조회 : 모든 page 의 내역과 기간 합계
+#[derive(Debug, Clone)]
+pub struct ProfitReport<T> {
+    pub records: Vec<T>,
+    pub summary: ProfitSummary,
+}
+
+impl<T: DeserializeOwned> ProfitReport<T> {
+    /// 연속조회를 합친 응답, 합계는 마지막 page 의 output2 사용
+    pub(crate) fn from_response(mut v: Value) -> KisResult<Self> {
+        let records = take_output(&mut v, "output1")?;
+        let summary = take_output(&mut v, "output2")?;
+        Ok(ProfitReport { records, summary })
+    }
+}
+
+fn order_side<'de, D: Deserializer<'de>>(deserializer: D) -> Result<OrderSide, D::Error> {
+    let code = String::deserialize(deserializer)?;
+    OrderSide::from_code(code.trim())
+        .ok_or_else(|| serde::de::Error::custom(format!("invalid sll_buy_dvsn_cd {code:?}")))
+}
+
+fn order_type<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<OrderType>, D::Error> {
+    let code = String::deserialize(deserializer)?;
+    Ok(OrderType::from_code(code.trim()))
+}
+
+fn yes_no<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    let flag = String::deserialize(deserializer)?;
+    Ok(flag.trim() == "Y")
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+
+    #[test]
+    fn test_account_balance() {
+        let v = serde_json::json!({
+            "rt_cd": "0",
+            "output1": [{
+                "pdno": "005930", "prdt_name": "삼성전자", "trad_dvsn_name": "현금",
+                "hldg_qty": "10", "ord_psbl_qty": "8", "pchs_avg_pric": "68500.0000",
+                "pchs_amt": "685000", "prpr": "70000", "evlu_amt": "700000",
+                "evlu_pfls_amt": "15000", "evlu_pfls_rt": "2.19"
+            }],
+            "output2": [{
+                "dnca_tot_amt": "1000000", "nxdy_excc_amt": "1000000", "prvs_rcdl_excc_amt": "315000",
+                "scts_evlu_amt": "700000", "tot_evlu_amt": "1015000", "nass_amt": "1015000",
+                "evlu_pfls_smtl_amt": "15000"
+            }]
+        });
+
+        let balance = AccountBalance::from_response(v).unwrap();
+        let holding = &balance.holdings[0];
+        assert_eq!(holding.ticker, "005930");
+        assert_eq!(holding.quantity, 10);
+        assert_eq!(holding.orderable_quantity, 8);
+        assert_eq!(holding.avg_price, 68500.0);
+        assert_eq!(holding.pnl_rate, 2.19);
+        assert_eq!(holding.extra["trad_dvsn_name"], "현금");
+        assert_eq!(balance.summary.deposit_d2, 315000);
+        assert_eq!(balance.summary.net_asset, 1015000);
+
+        let v = serde_json::json!({"output1": [], "output2": []});
+        assert!(matches!(
+            AccountBalance::from_response(v),
+            Err(Error::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_buying_power() {
+        let v = serde_json::json!({
+            "ord_psbl_cash": "1000000", "ord_psbl_sbst": "0", "ruse_psbl_amt": "0",
+            "psbl_qty_calc_unpr": "70000", "nrcvb_buy_amt": "980000", "nrcvb_buy_qty": "14",
+            "max_buy_amt": "3200000", "max_buy_qty": "45", "cma_evlu_amt": "0"
+        });
+        let power: BuyingPower = serde_json::from_value(v).unwrap();
+        assert_eq!(power.cash, 1000000);
+        assert_eq!(power.cash_quantity, 14);
+        assert_eq!(power.max_quantity, 45);
+        assert_eq!(power.bound_quantity(10), 10);
+        assert_eq!(power.bound_quantity(100), 14);
+    }
+
+    #[test]
+    fn test_execution() {
+        let v = serde_json::json!({
+            "ord_dt": "20240105", "ord_gno_brno": "91252", "odno": "0000117057", "orgn_odno": "",
+            "ord_dvsn_name": "지정가", "sll_buy_dvsn_cd": "02", "sll_buy_dvsn_cd_name": "현금매수",
+            "pdno": "005930", "prdt_name": "삼성전자", "ord_qty": "10", "ord_unpr": "70000",
+            "ord_tmd": "121052", "tot_ccld_qty": "10", "avg_prvs": "69900", "cncl_yn": "N",
+            "tot_ccld_amt": "699000", "ord_dvsn_cd": "00", "cncl_cfrm_qty": "0", "rmn_qty": "0",
+            "rjct_qty": "0"
+        });
+        let execution: Execution = serde_json::from_value(v).unwrap();
+        assert_eq!(execution.side, OrderSide::Buy);
+        assert_eq!(execution.order_type, Some(OrderType::Limit));
+        assert_eq!(execution.avg_price, 69900.0);
+        assert!(execution.is_filled());
+        assert!(!execution.cancelled);
+        assert_eq!(execution.ack().order_no, "0000117057");
+    }
+
+    #[test]
+    fn test_execution_query() {
+        let query = ExecutionQuery::new("20240101", "20240131")
+            .side(OrderSide::Sell)
+            .ticker("005930")
+            .fill(FillFilter::Unfilled);
+        assert!(query.validate().is_ok());
+        assert_eq!(query.get_fill().code(), "02");
+        assert!(ExecutionQuery::today().validate().is_ok());
+
+        for query in [
+            ExecutionQuery::new("20240131", "20240101"),
+            ExecutionQuery::new("2024-01-01", "20240131"),
+            ExecutionQuery::new("20240101", "20240131").ticker("5930"),
+        ] {
+            assert!(matches!(query.validate(), Err(Error::InvalidRequest(_))));
+        }
+    }
+
+    #[test]
+    fn test_execution_query_3_months() {
+        assert_eq!(months_ago("20240515", 3), "20240215");
+        assert_eq!(months_ago("20240531", 3), "20240229");
+        assert_eq!(months_ago("20240210", 3), "20231110");
+
+        let today = "20240515";
+        let recent = ExecutionQuery::new("20240215", "20240515");
+        assert!(!recent.is_before_3_months_from(today).unwrap());
+        let old = ExecutionQuery::new("20240101", "20240214");
+        assert!(old.is_before_3_months_from(today).unwrap());
+        assert!(matches!(
+            ExecutionQuery::new("20240101", "20240301").is_before_3_months_from(today),
+            Err(Error::InvalidRequest(_))
+        ));
+        assert!(!ExecutionQuery::today().is_before_3_months().unwrap());
+    }
+
+    #[test]
+    fn test_profit_report() {
+        let v = serde_json::json!({
+            "rt_cd": "0",
+            "output1": [{
+                "trad_dt": "20240105", "pdno": "005930", "prdt_name": "삼성전자", "trad_dvsn_name": "매도",
+                "loan_dt": "", "hldg_qty": "0", "pchs_unpr": "68500", "buy_qty": "0", "buy_amt": "685000",
+                "sll_pric": "70000", "sll_qty": "10", "sll_amt": "700000", "rlzt_pfls": "12990",
+                "pfls_rt": "1.89", "fee": "210", "tl_tax": "1800", "loan_int": "0"
+            }],
+            "output2": {
+                "sll_qty_sum": "10", "sll_tr_amt_sum": "700000", "sll_fee_smtl": "105", "sll_tltx_smtl": "1800",
+                "buyqty_smtl": "10", "buy_tr_amt_smtl": "685000", "buy_fee_smtl": "105", "buy_tax_smtl": "0",
+                "tot_fee": "210", "tot_tltx": "1800", "tot_rlzt_pfls": "12990", "loan_int": "0", "tot_pftrt": "1.89"
+            }
+        });
+        let report = ProfitReport::<TradeProfit>::from_response(v).unwrap();
+        let trade = &report.records[0];
+        assert_eq!(
+            trade.sell_amount - trade.buy_amount - trade.fee - trade.tax,
+            trade.realized_profit
+        );
+        assert_eq!(trade.extra["trad_dvsn_name"], "매도");
+        assert_eq!(report.summary.buy_quantity, 10);
+        assert_eq!(report.summary.realized_profit, 12990);
+
+        let v = serde_json::json!({
+            "output1": [{
+                "trad_dt": "20240105", "buy_amt": "0", "sll_amt": "700000", "rlzt_pfls": "12990",
+                "fee": "105", "loan_int": "0", "tl_tax": "1800", "pfls_rt": "1.89", "sll_qty1": "10", "buy_qty1": "0"
+            }],
+            "output2": {"sll_qty_sum": "10", "buy_qty_smtl": "0", "tot_rlzt_pfls": "12990"}
+        });
+        let report = ProfitReport::<DailyProfit>::from_response(v).unwrap();
+        assert_eq!(report.records[0].sell_quantity, 10);
+        assert_eq!(report.summary.sell_quantity, 10);
+
+        assert!(ProfitQuery::new("20240101", "20240131")
+            .ticker("005930")
+            .validate()
+            .is_ok());
+        assert!(matches!(
+            ProfitQuery::new("20240131", "20240101").validate(),
+            Err(Error::InvalidRequest(_))
+        ));
+    }
+}
@@ -1,141 +1,1165 @@
-use super::account::AccountConfig;
+//! 실시간 (웹소켓) 연결
+//!
+//! 연결 하나를 thread 하나가 소유하고, 구독/해제 요청은 channel 로 전달받는다.
+//! 받은 데이터는 tr_id 별로 파싱해 구독한 tr_key 의 channel 로 보낸다.
+//! 연결이 끊기거나 응답이 없으면 backoff 후 다시 연결해 구독을 복원한다.
+
+use std::any::Any;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::ErrorKind;
+use std::net::TcpStream;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 
 use serde_json::json;
-use tungstenite::{connect, Message};
+use tungstenite::handshake::HandshakeError;
+use tungstenite::{client, Message, WebSocket};
 use url::Url;
 
-use std::sync::mpsc::Sender;
-// use std::{sync::mpsc, thread};
-
-// type KisResult<T> = Result<T, Box<dyn std::error::Error>>;
-
-pub fn websoket_test(conf: &AccountConfig, ticker: &str, tx: Sender<String>) {
-    let params = json!({
-      "header": {
-        "appkey": conf.get_apikey(),
-        "appsecret": conf.get_secret(),
-        "custtype": "P",
-        "tr_type": "1",
-        "content-type": "utf-8"
-      },
-      "body": {
-        "input": {
-          "tr_id": "H0STASP0",
-          "tr_key": ticker
-        }
-      }
-    });
-
-    println!("Req Header {} \nBody {}", params["header"], params["body"]);
-
-    // let url = 'ws://ops.koreainvestment.com:21000' //실전투자
-    let url = "ws://ops.koreainvestment.com:31000"; //모의투자
-    let (mut socket, response) = connect(Url::parse(url).unwrap()).expect("Can't connect");
-
-    println!("Connected to the server");
-    println!("Response HTTP code: {}", response.status());
-    println!("Response contains the following headers:");
-
-    for (ref header, _value) in response.headers() {
-        println!("* {:?}", header);
-    }
-
-    socket
-        .write_message(Message::Text(params.to_string()))
-        .unwrap();
-
-    loop {
-        println!("Loop In");
-        let msg = socket
-            .read_message()
-            .expect("Error reading message");
-        // println!("Received: {}", msg);
-
-        let data = msg.to_string();
-        let data = data.split('|').collect::<Vec<&str>>();
-
-        if data[0] == "0" {
-            let values = data[3]
-                .split('^')
-                .collect::<Vec<&str>>();
-            println!("{:?}", values);
-            print_order_book(&values);
-        }
-
-        // print_order_book(&msg.to_string());
-        // ?tx.send(format!("Received: {}", msg));
-    }
-    // socket
-}
-
-fn print_order_book(recvvalue: &[&str]) {
-    println!("유가증권 단축 종목코드 {}", recvvalue[0]);
-    println!(
-        "영업시간 [{}] 시간구분코드 [{}]",
-        recvvalue[1], recvvalue[2]
-    );
-    println!("======================================");
-    println!("매도호가10 {}    잔량10 {}", recvvalue[12], recvvalue[32]);
-    println!("매도호가09 {}    잔량09 {}", recvvalue[11], recvvalue[31]);
-    println!("매도호가08 {}    잔량08 {}", recvvalue[10], recvvalue[30]);
-    println!("매도호가07 {}    잔량07 {}", recvvalue[9], recvvalue[29]);
-    println!("매도호가06 {}    잔량06 {}", recvvalue[8], recvvalue[28]);
-    println!("매도호가05 {}    잔량05 {}", recvvalue[7], recvvalue[27]);
-    println!("매도호가04 {}    잔량04 {}", recvvalue[6], recvvalue[26]);
-    println!("매도호가03 {}    잔량03 {}", recvvalue[5], recvvalue[25]);
-    println!("매도호가02 {}    잔량02 {}", recvvalue[4], recvvalue[24]);
-    println!("매도호가01 {}    잔량01 {}", recvvalue[3], recvvalue[23]);
-    println!("------------------------------");
-    println!("매수호가01 {}    잔량01 {}", recvvalue[13], recvvalue[33]);
-    println!("매수호가02 {}    잔량02 {}", recvvalue[14], recvvalue[34]);
-    println!("매수호가03 {}    잔량03 {}", recvvalue[15], recvvalue[35]);
-    println!("매수호가04 {}    잔량04 {}", recvvalue[16], recvvalue[36]);
-    println!("매수호가05 {}    잔량05 {}", recvvalue[17], recvvalue[37]);
-    println!("매수호가06 {}    잔량06 {}", recvvalue[18], recvvalue[38]);
-    println!("매수호가07 {}    잔량07 {}", recvvalue[19], recvvalue[39]);
-    println!("매수호가08 {}    잔량08 {}", recvvalue[20], recvvalue[40]);
-    println!("매수호가09 {}    잔량09 {}", recvvalue[21], recvvalue[41]);
-    println!("매수호가10 {}    잔량10 {}", recvvalue[22], recvvalue[42]);
-    println!("======================================");
-    println!("총매도호가 잔량        {}", recvvalue[43]);
-    println!("총매도호가 잔량 증감    {}", recvvalue[54]);
-    println!("총매수호가 잔량        {}", recvvalue[44]);
-    println!("총매수호가 잔량 증감    {}", recvvalue[55]);
-    println!("시간외 총매도호가 잔량   {}", recvvalue[45]);
-    println!("시간외 총매수호가 증감   {}", recvvalue[46]);
-    println!("시간외 총매도호가 잔량   {}", recvvalue[56]);
-    println!("시간외 총매수호가 증감   {}", recvvalue[57]);
-    println!("예상 체결가           {}", recvvalue[47]);
-    println!("예상 체결량           {}", recvvalue[48]);
-    println!("예상 거래량           {}", recvvalue[49]);
-    println!("예상체결 대비          {}", recvvalue[50]);
-    println!("부호                 {}", recvvalue[51]);
-    println!("예상체결 전일대비율    {}", recvvalue[52]);
-    println!("누적거래량            {}", recvvalue[53]);
-    println!("주식매매 구분코드     {}", recvvalue[58]);
+use super::api::KisApi;
+use super::order::validate_ticker;
+use super::realtime::{parse_control, parse_frame, parse_records, Control, PayloadCipher};
+use super::realtime::{ExecutionNotice, RealtimeOrderBook, RealtimeRecord, Tick};
+use super::realtime::{EXECUTION_TR_ID, VIRTUAL_EXECUTION_TR_ID};
+use super::retry::RetryPolicy;
+use super::{Error, KisResult};
+
+/// 세션 (접속키) 당 실시간 데이터 등록 한도
+pub const MAX_SUBSCRIPTIONS: usize = 41;
+/// 이 시간 동안 PINGPONG 을 포함해 아무것도 받지 못하면 끊긴 연결로 봄
+pub const STALE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 구독/해제 요청을 확인하는 주기
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// 구독 응답 대기 시간
+const ACK_TIMEOUT: Duration = Duration::from_secs(10);
+/// 연결과 handshake 제한 시간, 재연결 중 client 를 닫을 때도 이 시간 안에 끝남
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+type Socket = WebSocket<TcpStream>;
+
+/// 연결 상태 변화와 처리하지 못한 수신 데이터
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    /// 다시 연결하고 구독 재등록을 요청함, 거절된 구독은 `Rejected` 로 알림
+    Connected,
+    /// socket 오류, 서버 종료, 또는 `stale_timeout` 동안 수신 없음
+    Disconnected { reason: String },
+    /// `attempt` 번째 재연결 전 `delay` 대기
+    Reconnecting { attempt: u32, delay: Duration },
+    /// 재연결을 포기함, 구독 channel 들이 닫힘
+    Closed,
+    /// 재연결 후 재등록이 거절되어 해당 구독의 channel 이 닫힘
+    ///
+    /// 접속키는 다시 발급하지 않으므로 만료 (24시간) 후 재연결하면 모든 구독이 거절된다.
+    Rejected {
+        tr_id: String,
+        tr_key: String,
+        reason: String,
+    },
+    /// 받은 frame 을 버림 (복호화 key 를 받기 전, 복호화 또는 파싱 실패)
+    Dropped { tr_id: String, reason: String },
+}
+
+/// 연결 하나로 여러 tr_id, 종목을 구독하는 실시간 client
+///
+/// drop 되면 연결을 닫고, 구독 channel 들도 닫힌다.
+pub struct RealtimeClient {
+    commands: Sender<Command>,
+    worker: Option<JoinHandle<()>>,
+    hts_id: String,
+    real: bool,
+}
+
+impl RealtimeClient {
+    /// 접속키를 발급받아 계좌 설정의 실시간 주소로 연결
+    ///
+    /// 끊기면 1초부터 최대 1분 간격으로 10회까지 재연결을 시도한다.
+    pub fn connect(kis: &KisApi) -> KisResult<Self> {
+        let reconnect = RetryPolicy::new(10, Duration::from_secs(1), Duration::from_secs(60));
+        Self::connect_with(kis, reconnect, STALE_TIMEOUT)
+    }
+
+    /// `reconnect` : 끊긴 뒤 재연결 횟수와 backoff, `stale_timeout` : 끊긴 연결로 볼 무수신 시간
+    pub fn connect_with(
+        kis: &KisApi,
+        reconnect: RetryPolicy,
+        stale_timeout: Duration,
+    ) -> KisResult<Self> {
+        let conf = kis.account_info();
+        let approval_key = kis.issue_approval_key()?;
+        let session = Session {
+            url: conf.get_ws_url(),
+            approval_key,
+            reconnect,
+            stale_timeout,
+        };
+        Self::connect_to(session, conf.get_hts_id(), conf.is_real())
+    }
+
+    fn connect_to(session: Session, hts_id: &str, real: bool) -> KisResult<Self> {
+        let socket = session.open()?;
+        let (commands, rx) = mpsc::channel();
+        let worker = Worker {
+            socket,
+            session,
+            commands: rx,
+            routes: HashMap::new(),
+            active: HashSet::new(),
+            pending: HashMap::new(),
+            ciphers: HashMap::new(),
+            watchers: Vec::new(),
+            last_received: Instant::now(),
+        };
+        Ok(RealtimeClient {
+            commands,
+            worker: Some(thread::spawn(move || worker.run())),
+            hts_id: hts_id.to_string(),
+            real,
+        })
+    }
+
+    /// 연결 상태 변화와 버려진 frame (`ConnectionEvent`) 을 받을 channel
+    pub fn connection_events(&self) -> KisResult<Receiver<ConnectionEvent>> {
+        let (tx, rx) = mpsc::channel();
+        self.send(Command::Watch(tx))?;
+        Ok(rx)
+    }
+
+    /// 실시간 주식호가 (H0STASP0)
+    pub fn subscribe_order_book(&self, ticker: &str) -> KisResult<Receiver<RealtimeOrderBook>> {
+        validate_ticker(ticker)?;
+        self.subscribe(RealtimeOrderBook::TR_ID, ticker)
+    }
+
+    /// 실시간 주식체결가 (H0STCNT0)
+    pub fn subscribe_ticks(&self, ticker: &str) -> KisResult<Receiver<Tick>> {
+        validate_ticker(ticker)?;
+        self.subscribe(Tick::TR_ID, ticker)
+    }
+
+    /// 계좌의 실시간 체결통보 (실전 H0STCNI0, 모의 H0STCNI9), HTS ID 로 구독
+    pub fn subscribe_executions(&self) -> KisResult<Receiver<ExecutionNotice>> {
+        self.subscribe(self.execution_tr_id(), &self.hts_id)
+    }
+
+    /// 구독 해제, 해당 구독의 channel 들은 닫힌다
+    pub fn unsubscribe(&self, tr_id: &str, tr_key: &str) -> KisResult<()> {
+        let (reply, result) = mpsc::channel();
+        self.send(Command::Unsubscribe {
+            tr_id: tr_id.to_string(),
+            tr_key: tr_key.to_string(),
+            reply,
+        })?;
+        wait(result)
+    }
+
+    /// 체결통보 구독 해제
+    pub fn unsubscribe_executions(&self) -> KisResult<()> {
+        self.unsubscribe(self.execution_tr_id(), &self.hts_id)
+    }
+
+    fn execution_tr_id(&self) -> &'static str {
+        if self.real {
+            EXECUTION_TR_ID
+        } else {
+            VIRTUAL_EXECUTION_TR_ID
+        }
+    }
+
+    fn subscribe<T>(&self, tr_id: &str, tr_key: &str) -> KisResult<Receiver<T>>
+    where
+        T: RealtimeRecord + Clone + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel();
+        let (reply, result) = mpsc::channel();
+        self.send(Command::Subscribe {
+            tr_id: tr_id.to_string(),
+            tr_key: tr_key.to_string(),
+            route: Box::new(Channel {
+                senders: vec![(tr_key.to_string(), tx)],
+            }),
+            reply,
+        })?;
+        wait(result)?;
+        Ok(rx)
+    }
+
+    fn send(&self, command: Command) -> KisResult<()> {
+        self.commands
+            .send(command)
+            .map_err(|_| Error::Realtime("connection closed".to_string()))
+    }
+}
+
+impl Drop for RealtimeClient {
+    fn drop(&mut self) {
+        let _ = self.commands.send(Command::Close);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// 구독/해제 결과 대기
+fn wait(result: Receiver<KisResult<()>>) -> KisResult<()> {
+    match result.recv_timeout(ACK_TIMEOUT) {
+        Ok(res) => res,
+        Err(RecvTimeoutError::Timeout) => Err(Error::Realtime(
+            "no response to subscribe request".to_string(),
+        )),
+        Err(RecvTimeoutError::Disconnected) => {
+            Err(Error::Realtime("connection closed".to_string()))
+        }
+    }
+}
+
+enum Command {
+    Subscribe {
+        tr_id: String,
+        tr_key: String,
+        route: Box<dyn Route>,
+        reply: Sender<KisResult<()>>,
+    },
+    Unsubscribe {
+        tr_id: String,
+        tr_key: String,
+        reply: Sender<KisResult<()>>,
+    },
+    Watch(Sender<ConnectionEvent>),
+    Close,
+}
+
+/// tr_id 하나의 구독자들, tr_id 마다 record 타입이 정해져 있음
+trait Route: Send {
+    /// record 를 tr_key 가 같은 구독자에게 전달, 구독자가 모두 떠난 tr_key 목록을 돌려줌
+    fn dispatch(&mut self, payload: &str, count: usize) -> KisResult<Vec<String>>;
+
+    /// 같은 tr_id 의 새 구독자 추가
+    fn absorb(&mut self, other: Box<dyn Route>);
+
+    fn remove(&mut self, tr_key: &str);
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+struct Channel<T> {
+    senders: Vec<(String, Sender<T>)>,
+}
+
+impl<T: RealtimeRecord + Clone + Send + 'static> Route for Channel<T> {
+    fn dispatch(&mut self, payload: &str, count: usize) -> KisResult<Vec<String>> {
+        let records = parse_records::<T>(payload, count)?;
+        let before: HashSet<String> = self.senders.iter().map(|(key, _)| key.clone()).collect();
+
+        for record in records {
+            let tr_key = record.tr_key();
+            // 받는 쪽이 닫힌 sender 는 제거
+            self.senders.retain(|(key, tx)| {
+                if tr_key.is_some_and(|k| k != key) {
+                    return true;
+                }
+                tx.send(record.clone()).is_ok()
+            });
+        }
+
+        Ok(before
+            .into_iter()
+            .filter(|k| !self.senders.iter().any(|(key, _)| key == k))
+            .collect())
+    }
+
+    fn absorb(&mut self, other: Box<dyn Route>) {
+        if let Ok(other) = other.into_any().downcast::<Channel<T>>() {
+            self.senders.extend(other.senders);
+        }
+    }
+
+    fn remove(&mut self, tr_key: &str) {
+        self.senders.retain(|(key, _)| key != tr_key);
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// 연결 주소와 재연결 설정
+struct Session {
+    url: String,
+    approval_key: String,
+    reconnect: RetryPolicy,
+    stale_timeout: Duration,
+}
+
+impl Session {
+    /// `ws://` 만 지원 (KIS 실시간 주소는 모두 ws), 읽기 timeout 이 있어야 요청 처리와 무수신 감지가 동작함
+    fn open(&self) -> KisResult<Socket> {
+        let url = Url::parse(&self.url)
+            .map_err(|e| Error::Config(format!("invalid websocket url: {e}")))?;
+        if url.scheme() != "ws" {
+            return Err(Error::Config(format!(
+                "unsupported websocket url (ws:// only): {url}"
+            )));
+        }
+        let addrs = url
+            .socket_addrs(|| None)
+            .map_err(|e| Error::Config(format!("invalid websocket url: {e}")))?;
+        let mut last_err = None;
+        let stream =
+            addrs.iter().find_map(
+                |addr| match TcpStream::connect_timeout(addr, CONNECT_TIMEOUT) {
+                    Ok(stream) => Some(stream),
+                    Err(e) => {
+                        last_err = Some(e);
+                        None
+                    }
+                },
+            );
+        let stream = match (stream, last_err) {
+            (Some(stream), _) => stream,
+            (None, Some(e)) => return Err(e.into()),
+            (None, None) => {
+                return Err(Error::Config(format!(
+                    "can not resolve websocket url: {url}"
+                )))
+            }
+        };
+
+        stream.set_read_timeout(Some(CONNECT_TIMEOUT))?;
+        stream.set_write_timeout(Some(CONNECT_TIMEOUT))?;
+        let (socket, _response) = client(url, stream).map_err(|e| match e {
+            HandshakeError::Failure(e) => Error::from(e),
+            HandshakeError::Interrupted(_) => {
+                Error::Realtime("websocket handshake timed out".to_string())
+            }
+        })?;
+        socket.get_ref().set_read_timeout(Some(POLL_INTERVAL))?;
+        Ok(socket)
+    }
+}
+
+/// 응답을 기다리는 등록/해제 요청, 같은 종목은 보낸 순서대로 쌓임
+struct PendingRequest {
+    /// 1 등록, 2 해제
+    tr_type: &'static str,
+    /// 결과를 기다리는 구독 요청, 해제와 재연결 후 재등록은 비어 있음
+    replies: Vec<Sender<KisResult<()>>>,
+}
+
+/// 연결을 소유하고 구독 요청과 수신 데이터를 처리하는 thread
+struct Worker {
+    socket: Socket,
+    session: Session,
+    commands: Receiver<Command>,
+    /// tr_id 별 구독자
+    routes: HashMap<String, Box<dyn Route>>,
+    /// 서버에 등록된 (tr_id, tr_key), 재연결 후 다시 등록함
+    active: HashSet<(String, String)>,
+    /// 구독 응답을 기다리는 요청, 응답 전에 같은 구독을 요청한 곳도 함께 기다림
+    pending: HashMap<(String, String), VecDeque<PendingRequest>>,
+    /// 암호화되는 tr_id 의 복호화 key
+    ciphers: HashMap<String, PayloadCipher>,
+    /// 연결 상태 변화를 받는 channel
+    watchers: Vec<Sender<ConnectionEvent>>,
+    last_received: Instant,
+}
+
+impl Worker {
+    fn run(mut self) {
+        loop {
+            let err = match self.serve() {
+                Ok(()) => {
+                    let _ = self.socket.close(None);
+                    let _ = self.socket.write_pending();
+                    return;
+                }
+                Err(err) => err,
+            };
+            self.disconnected(err);
+            if !self.reconnect() {
+                self.publish(ConnectionEvent::Closed);
+                return;
+            }
+        }
+    }
+
+    /// 연결이 끊길 때까지 요청과 수신 데이터 처리, client 가 닫히면 `Ok(())`
+    fn serve(&mut self) -> KisResult<()> {
+        loop {
+            loop {
+                match self.commands.try_recv() {
+                    Ok(Command::Close) | Err(TryRecvError::Disconnected) => return Ok(()),
+                    Ok(command) => self.handle(command)?,
+                    Err(TryRecvError::Empty) => break,
+                }
+            }
+
+            match self.socket.read_message() {
+                Ok(message) => {
+                    self.last_received = Instant::now();
+                    match message {
+                        Message::Text(text) => self.on_text(&text)?,
+                        Message::Close(_) => {
+                            return Err(Error::Realtime("closed by server".to_string()))
+                        }
+                        _ => {}
+                    }
+                }
+                Err(tungstenite::Error::Io(e))
+                    if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+                {
+                    if self.last_received.elapsed() > self.session.stale_timeout {
+                        return Err(Error::Realtime("no message from server".to_string()));
+                    }
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    fn handle(&mut self, command: Command) -> KisResult<()> {
+        match command {
+            Command::Subscribe {
+                tr_id,
+                tr_key,
+                route,
+                reply,
+            } => {
+                let key = (tr_id, tr_key);
+                if self.active.contains(&key) {
+                    self.add_route(&key.0, route);
+                    let queued = self
+                        .pending
+                        .get_mut(&key)
+                        .and_then(|requests| requests.iter_mut().find(|r| r.tr_type == "1"));
+                    match queued {
+                        Some(request) => request.replies.push(reply),
+                        None => {
+                            let _ = reply.send(Ok(()));
+                        }
+                    }
+                    return Ok(());
+                }
+                if self.active.len() >= MAX_SUBSCRIPTIONS {
+                    let _ = reply.send(Err(Error::SubscriptionLimit {
+                        limit: MAX_SUBSCRIPTIONS,
+                    }));
+                    return Ok(());
+                }
+                self.add_route(&key.0, route);
+                self.active.insert(key.clone());
+                self.request(&key.0, &key.1, "1", vec![reply])?;
+            }
+            Command::Unsubscribe {
+                tr_id,
+                tr_key,
+                reply,
+            } => {
+                let registered = self.forget(&tr_id, &tr_key);
+                let _ = reply.send(Ok(()));
+                if registered {
+                    self.request(&tr_id, &tr_key, "2", Vec::new())?;
+                }
+            }
+            Command::Watch(tx) => self.watchers.push(tx),
+            Command::Close => {}
+        }
+        Ok(())
+    }
+
+    fn on_text(&mut self, text: &str) -> KisResult<()> {
+        if let Ok(Some(control)) = parse_control(text) {
+            if control.is_pingpong() {
+                self.socket.write_message(Message::Text(text.to_string()))?;
+            } else {
+                self.on_control(control);
+            }
+            return Ok(());
+        }
+
+        let frame = match parse_frame(text) {
+            Ok(Some(frame)) => frame,
+            Ok(None) => return Ok(()),
+            Err(e) => {
+                self.dropped("", e);
+                return Ok(());
+            }
+        };
+        if !self.routes.contains_key(frame.tr_id) {
+            return Ok(());
+        }
+        let payload = match (frame.encrypted, self.ciphers.get(frame.tr_id)) {
+            (false, _) => Ok(frame.payload.to_string()),
+            (true, Some(cipher)) => cipher.decrypt(frame.payload),
+            (true, None) => Err(Error::Realtime(
+                "encrypted frame before the key".to_string(),
+            )),
+        };
+        let dispatched = payload.and_then(|payload| match self.routes.get_mut(frame.tr_id) {
+            Some(route) => route.dispatch(&payload, frame.count),
+            None => Ok(Vec::new()),
+        });
+        // 체결통보 등을 잃어버린 것이므로 버리지 않고 알림
+        let closed = match dispatched {
+            Ok(closed) => closed,
+            Err(e) => {
+                self.dropped(frame.tr_id, e);
+                return Ok(());
+            }
+        };
+
+        // 구독자가 모두 떠난 tr_key 는 등록 해제
+        let tr_id = frame.tr_id.to_string();
+        for tr_key in closed {
+            if self.active.remove(&(tr_id.clone(), tr_key.clone())) {
+                self.request(&tr_id, &tr_key, "2", Vec::new())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn on_control(&mut self, control: Control) {
+        if let Some(cipher) = control.cipher.clone() {
+            self.ciphers.insert(control.tr_id.clone(), cipher);
+        }
+        let key = (control.tr_id.clone(), control.tr_key.clone());
+        let request = match self.pending.get_mut(&key) {
+            Some(requests) => {
+                // 응답 종류를 알면 같은 종류의 가장 오래된 요청, 모르면 가장 오래된 요청
+                let position = match control.tr_type() {
+                    Some(tr_type) => requests.iter().position(|r| r.tr_type == tr_type),
+                    None => Some(0),
+                };
+                let request = position.and_then(|i| requests.remove(i));
+                if requests.is_empty() {
+                    self.pending.remove(&key);
+                }
+                request
+            }
+            None => None,
+        };
+        // 해제 응답은 이미 처리했으므로 무시
+        let replies = match request {
+            Some(request) if request.tr_type == "1" => request.replies,
+            _ => return,
+        };
+        if let Some(err) = control.error() {
+            // 재연결 후 재등록이 거절된 구독 (만료된 접속키 등) 은 channel 을 닫고 알림
+            if self.forget(&control.tr_id, &control.tr_key) && replies.is_empty() {
+                self.publish(ConnectionEvent::Rejected {
+                    tr_id: control.tr_id.clone(),
+                    tr_key: control.tr_key.clone(),
+                    reason: err.to_string(),
+                });
+            }
+        }
+        for reply in replies {
+            let _ = reply.send(control.error().map_or(Ok(()), Err));
+        }
+    }
+
+    /// 응답을 기다리던 구독은 실패 처리
+    fn disconnected(&mut self, err: Error) {
+        for ((tr_id, tr_key), requests) in std::mem::take(&mut self.pending) {
+            for request in requests {
+                if request.replies.is_empty() {
+                    continue;
+                }
+                self.forget(&tr_id, &tr_key);
+                for reply in request.replies {
+                    let _ = reply.send(Err(Error::Realtime(format!("connection lost: {err}"))));
+                }
+            }
+        }
+        self.ciphers.clear();
+        self.publish(ConnectionEvent::Disconnected {
+            reason: err.to_string(),
+        });
+    }
+
+    /// backoff 하며 다시 연결하고 구독을 재등록, 포기하거나 client 가 닫히면 `false`
+    fn reconnect(&mut self) -> bool {
+        let mut attempt = 0;
+        while attempt < self.session.reconnect.get_max_retries() {
+            attempt += 1;
+            let delay = self.session.reconnect.delay(attempt);
+            self.publish(ConnectionEvent::Reconnecting { attempt, delay });
+            if !self.sleep(delay) {
+                return false;
+            }
+
+            let socket = match self.session.open() {
+                Ok(socket) => socket,
+                Err(_) => continue,
+            };
+            self.socket = socket;
+            self.last_received = Instant::now();
+            if self.resubscribe().is_ok() {
+                self.publish(ConnectionEvent::Connected);
+                return true;
+            }
+        }
+        false
+    }
+
+    fn resubscribe(&mut self) -> KisResult<()> {
+        let active: Vec<_> = self.active.iter().cloned().collect();
+        for (tr_id, tr_key) in active {
+            self.request(&tr_id, &tr_key, "1", Vec::new())?;
+        }
+        Ok(())
+    }
+
+    /// 연결이 없는 동안 대기, 새 구독은 거절하고 해제는 바로 반영
+    fn sleep(&mut self, delay: Duration) -> bool {
+        let until = Instant::now() + delay;
+        loop {
+            let timeout = until.saturating_duration_since(Instant::now());
+            match self.commands.recv_timeout(timeout) {
+                Ok(Command::Close) | Err(RecvTimeoutError::Disconnected) => return false,
+                Ok(Command::Subscribe { reply, .. }) => {
+                    let _ = reply.send(Err(Error::Realtime("reconnecting".to_string())));
+                }
+                Ok(Command::Unsubscribe {
+                    tr_id,
+                    tr_key,
+                    reply,
+                }) => {
+                    self.forget(&tr_id, &tr_key);
+                    let _ = reply.send(Ok(()));
+                }
+                Ok(Command::Watch(tx)) => self.watchers.push(tx),
+                Err(RecvTimeoutError::Timeout) => return true,
+            }
+        }
+    }
+
+    fn dropped(&mut self, tr_id: &str, err: Error) {
+        self.publish(ConnectionEvent::Dropped {
+            tr_id: tr_id.to_string(),
+            reason: err.to_string(),
+        });
+    }
+
+    fn publish(&mut self, event: ConnectionEvent) {
+        self.watchers.retain(|tx| tx.send(event.clone()).is_ok());
+    }
+
+    /// 구독 정보와 channel 제거, 등록되어 있었으면 `true`
+    fn forget(&mut self, tr_id: &str, tr_key: &str) -> bool {
+        self.remove_route(tr_id, tr_key);
+        self.active.remove(&(tr_id.to_string(), tr_key.to_string()))
+    }
+
+    fn add_route(&mut self, tr_id: &str, route: Box<dyn Route>) {
+        match self.routes.get_mut(tr_id) {
+            Some(existing) => existing.absorb(route),
+            None => {
+                self.routes.insert(tr_id.to_string(), route);
+            }
+        }
+    }
+
+    fn remove_route(&mut self, tr_id: &str, tr_key: &str) {
+        if let Some(route) = self.routes.get_mut(tr_id) {
+            route.remove(tr_key);
+        }
+    }
+
+    /// tr_type 1 등록, 2 해제, `replies` 는 응답을 기다리는 구독 요청
+    fn request(
+        &mut self,
+        tr_id: &str,
+        tr_key: &str,
+        tr_type: &'static str,
+        replies: Vec<Sender<KisResult<()>>>,
+    ) -> KisResult<()> {
+        self.pending
+            .entry((tr_id.to_string(), tr_key.to_string()))
+            .or_default()
+            .push_back(PendingRequest { tr_type, replies });
+        let params = json!({
+          "header": {
+            "approval_key": self.session.approval_key,
+            "custtype": "P",
+            "tr_type": tr_type,
+            "content-type": "utf-8"
+          },
+          "body": {
+            "input": {
+              "tr_id": tr_id,
+              "tr_key": tr_key
+            }
+          }
+        });
+        self.socket
+            .write_message(Message::Text(params.to_string()))?;
+        Ok(())
+    }
+}
+
+/// `ticker` 의 실시간 호가 (H0STASP0) 를 `tx` 로 전달
+///
+/// 받는 쪽이 channel 을 닫으면 연결을 끊고 `Ok(())` 를 돌려준다.
+pub fn stream_order_book(
+    kis: &KisApi,
+    ticker: &str,
+    tx: Sender<RealtimeOrderBook>,
+) -> KisResult<()> {
+    let client = RealtimeClient::connect(kis)?;
+    forward(client.subscribe_order_book(ticker)?, tx)
+}
+
+/// `ticker` 의 실시간 체결가 (H0STCNT0) 를 `tx` 로 전달
+pub fn stream_ticks(kis: &KisApi, ticker: &str, tx: Sender<Tick>) -> KisResult<()> {
+    let client = RealtimeClient::connect(kis)?;
+    forward(client.subscribe_ticks(ticker)?, tx)
+}
+
+/// 계좌의 실시간 체결통보를 `tx` 로 전달
+pub fn stream_executions(kis: &KisApi, tx: Sender<ExecutionNotice>) -> KisResult<()> {
+    let client = RealtimeClient::connect(kis)?;
+    forward(client.subscribe_executions()?, tx)
+}
+
+fn forward<T>(rx: Receiver<T>, tx: Sender<T>) -> KisResult<()> {
+    for record in rx {
+        if tx.send(record).is_err() {
+            return Ok(());
+        }
+    }
+    Err(Error::Realtime("connection closed".to_string()))
 }
 
 #[cfg(test)]
 mod unit_websoket {
-    use std::{sync::mpsc, thread};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
 
     use crate::kis::load_account_config;
 
-    use super::websoket_test;
+    use super::*;
+
+    type Requests = Arc<Mutex<Vec<(String, String, String)>>>;
+    type MockSocket = WebSocket<TcpStream>;
+
+    /// 연결마다 `handler` 를 실행하는 mock 서버
+    fn mock_listener<F>(mut handler: F) -> String
+    where
+        F: FnMut(usize, MockSocket) + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for (n, stream) in listener.incoming().enumerate() {
+                handler(n, tungstenite::accept(stream.unwrap()).unwrap());
+            }
+        });
+        url
+    }
+
+    /// 등록/해제 요청 하나를 읽어 기록하고 성공 응답, 연결이 끊기면 `None`
+    fn answer(socket: &mut MockSocket, log: &Requests) -> Option<(String, String, String)> {
+        let req: serde_json::Value = loop {
+            match socket.read_message() {
+                Ok(Message::Text(text)) => break serde_json::from_str(&text).unwrap(),
+                Ok(Message::Close(_)) | Err(_) => return None,
+                Ok(_) => continue,
+            }
+        };
+        let text = |v: &serde_json::Value| v.as_str().unwrap().to_string();
+        let tr_type = text(&req["header"]["tr_type"]);
+        let tr_id = text(&req["body"]["input"]["tr_id"]);
+        let tr_key = text(&req["body"]["input"]["tr_key"]);
+        log.lock()
+            .unwrap()
+            .push((tr_type.clone(), tr_id.clone(), tr_key.clone()));
+
+        let msg1 = if tr_type == "1" {
+            "SUBSCRIBE SUCCESS"
+        } else {
+            "UNSUBSCRIBE SUCCESS"
+        };
+        let ack = json!({
+            "header": {"tr_id": tr_id, "tr_key": tr_key, "encrypt": "N"},
+            "body": {"rt_cd": "0", "msg_cd": "OPSP0000", "msg1": msg1}
+        });
+        let _ = socket.write_message(Message::Text(ack.to_string()));
+        Some((tr_type, tr_id, tr_key))
+    }
+
+    /// 등록/해제 요청마다 성공 응답, 등록 후에는 `on_subscribe` 가 돌려준 frame 을 이어서 보내는 mock 서버
+    fn mock_ws_server<F>(on_subscribe: F) -> (String, Requests)
+    where
+        F: Fn(&str, &str) -> Vec<String> + Send + 'static,
+    {
+        let requests = Requests::default();
+        let log = requests.clone();
+        let url = mock_listener(move |_, mut socket| {
+            while let Some((tr_type, tr_id, tr_key)) = answer(&mut socket, &log) {
+                if tr_type == "1" {
+                    for frame in on_subscribe(&tr_id, &tr_key) {
+                        let _ = socket.write_message(Message::Text(frame));
+                    }
+                }
+            }
+        });
+        (url, requests)
+    }
+
+    fn mock_session(url: &str, stale_timeout: Duration) -> Session {
+        Session {
+            url: url.to_string(),
+            approval_key: "approval-key".to_string(),
+            reconnect: RetryPolicy::new(3, Duration::from_millis(10), Duration::from_millis(50)),
+            stale_timeout,
+        }
+    }
+
+    fn mock_client(url: &str) -> RealtimeClient {
+        RealtimeClient::connect_to(mock_session(url, STALE_TIMEOUT), "hts-id", false).unwrap()
+    }
+
+    fn mock_tick(ticker: &str) -> String {
+        let mut fields = vec!["0"; 46];
+        fields[0] = ticker;
+        fields[2] = "70100";
+        format!("0|H0STCNT0|001|{}", fields.join("^"))
+    }
+
+    fn request(tr_type: &str, tr_id: &str, tr_key: &str) -> (String, String, String) {
+        (tr_type.to_string(), tr_id.to_string(), tr_key.to_string())
+    }
+
+    #[test]
+    fn test_route_by_subscription() {
+        let (url, requests) = mock_ws_server(|tr_id, tr_key| match tr_id {
+            "H0STCNT0" => vec![mock_tick("000660"), mock_tick(tr_key)],
+            _ => Vec::new(),
+        });
+        let client = mock_client(&url);
+
+        let samsung = client.subscribe_ticks("005930").unwrap();
+        let tick = samsung.recv_timeout(ACK_TIMEOUT).unwrap();
+        assert_eq!(tick.ticker, "005930");
+        assert_eq!(tick.price, 70100);
+
+        let hynix = client.subscribe_ticks("000660").unwrap();
+        for _ in 0..2 {
+            let tick = hynix.recv_timeout(ACK_TIMEOUT).unwrap();
+            assert_eq!(tick.ticker, "000660");
+        }
+
+        client.unsubscribe(Tick::TR_ID, "005930").unwrap();
+        // 해제된 구독의 channel 은 닫힘
+        while samsung.recv_timeout(ACK_TIMEOUT).is_ok() {}
+
+        // 응답을 기다려 앞의 요청이 모두 처리되게 함
+        let _book = client.subscribe_order_book("005930").unwrap();
+        assert_eq!(
+            *requests.lock().unwrap(),
+            [
+                request("1", "H0STCNT0", "005930"),
+                request("1", "H0STCNT0", "000660"),
+                request("2", "H0STCNT0", "005930"),
+                request("1", "H0STASP0", "005930"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_subscription_limit() {
+        let (url, requests) = mock_ws_server(|_, _| Vec::new());
+        let client = mock_client(&url);
+
+        let tickers: Vec<String> = (0..MAX_SUBSCRIPTIONS).map(|i| format!("{i:06}")).collect();
+        let _receivers: Vec<_> = tickers
+            .iter()
+            .map(|ticker| client.subscribe_order_book(ticker).unwrap())
+            .collect();
+
+        // 이미 등록된 종목은 한도와 상관없이 channel 만 추가
+        assert!(client.subscribe_order_book(&tickers[0]).is_ok());
+        assert!(matches!(
+            client.subscribe_ticks("005930"),
+            Err(Error::SubscriptionLimit {
+                limit: MAX_SUBSCRIPTIONS
+            })
+        ));
+
+        // 해제하면 다시 등록 가능
+        client
+            .unsubscribe(RealtimeOrderBook::TR_ID, &tickers[0])
+            .unwrap();
+        assert!(client.subscribe_ticks("005930").is_ok());
+        assert_eq!(requests.lock().unwrap().len(), MAX_SUBSCRIPTIONS + 2);
+    }
+
+    #[test]
+    fn test_pingpong_echo() {
+        let ping = r#"{"header":{"tr_id":"PINGPONG","datetime":"20240105093001"}}"#;
+        let echoed = Arc::new(Mutex::new(String::new()));
+        let echo = echoed.clone();
+        let log = Requests::default();
+        let url = mock_listener(move |_, mut socket| {
+            answer(&mut socket, &log);
+            let _ = socket.write_message(Message::Text(ping.to_string()));
+            loop {
+                match socket.read_message() {
+                    Ok(Message::Text(text)) => break *echo.lock().unwrap() = text,
+                    Ok(_) => continue,
+                    Err(_) => return,
+                }
+            }
+            // 응답을 받은 뒤에 데이터를 보냄
+            let _ = socket.write_message(Message::Text(mock_tick("005930")));
+            while answer(&mut socket, &log).is_some() {}
+        });
+        let client = mock_client(&url);
+
+        let ticks = client.subscribe_ticks("005930").unwrap();
+        let tick = ticks.recv_timeout(ACK_TIMEOUT).unwrap();
+        assert_eq!(tick.ticker, "005930");
+        assert_eq!(*echoed.lock().unwrap(), ping);
+    }
+
+    #[test]
+    fn test_reconnect_resubscribe() {
+        let requests = Requests::default();
+        let log = requests.clone();
+        let url = mock_listener(move |n, mut socket| {
+            answer(&mut socket, &log);
+            answer(&mut socket, &log);
+            if n == 0 {
+                let _ = socket.close(None);
+                let _ = socket.write_pending();
+                return;
+            }
+            let _ = socket.write_message(Message::Text(mock_tick("005930")));
+            while answer(&mut socket, &log).is_some() {}
+        });
+        let client = mock_client(&url);
+        let events = client.connection_events().unwrap();
+
+        let ticks = client.subscribe_ticks("005930").unwrap();
+        let _book = client.subscribe_order_book("000660").unwrap();
+        let tick = ticks.recv_timeout(ACK_TIMEOUT).unwrap();
+        assert_eq!(tick.ticker, "005930");
+
+        assert!(matches!(
+            events.recv_timeout(ACK_TIMEOUT).unwrap(),
+            ConnectionEvent::Disconnected { .. }
+        ));
+        assert!(matches!(
+            events.recv_timeout(ACK_TIMEOUT).unwrap(),
+            ConnectionEvent::Reconnecting { attempt: 1, .. }
+        ));
+        assert_eq!(
+            events.recv_timeout(ACK_TIMEOUT).unwrap(),
+            ConnectionEvent::Connected
+        );
+
+        // 재연결 후 같은 구독을 다시 등록 (순서는 상관없음)
+        let requests = requests.lock().unwrap().clone();
+        assert_eq!(requests.len(), 4);
+        assert_eq!(
+            requests[..2].iter().collect::<HashSet<_>>(),
+            requests[2..].iter().collect::<HashSet<_>>()
+        );
+    }
+
+    #[test]
+    fn test_stale_connection() {
+        let log = Requests::default();
+        let mut idle = Vec::new();
+        let url = mock_listener(move |n, mut socket| {
+            answer(&mut socket, &log);
+            if n == 0 {
+                // 연결은 유지하되 아무것도 보내지 않음
+                idle.push(socket);
+                return;
+            }
+            let _ = socket.write_message(Message::Text(mock_tick("005930")));
+            while answer(&mut socket, &log).is_some() {}
+        });
+        let session = mock_session(&url, Duration::from_millis(300));
+        let client = RealtimeClient::connect_to(session, "hts-id", false).unwrap();
+        let events = client.connection_events().unwrap();
+
+        let ticks = client.subscribe_ticks("005930").unwrap();
+        let tick = ticks.recv_timeout(ACK_TIMEOUT).unwrap();
+        assert_eq!(tick.ticker, "005930");
+        assert_eq!(
+            events.recv_timeout(ACK_TIMEOUT).unwrap(),
+            ConnectionEvent::Disconnected {
+                reason: "realtime error: no message from server".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_dropped_frames() {
+        let (url, _requests) = mock_ws_server(|tr_id, tr_key| match tr_id {
+            // 복호화 key 없이 암호화된 체결통보, 필드가 모자란 체결가
+            "H0STCNI9" => vec![format!("1|H0STCNI9|001|{tr_key}")],
+            "H0STCNT0" => vec![
+                format!("0|H0STCNT0|001|{tr_key}^093001^70100"),
+                mock_tick(tr_key),
+            ],
+            _ => Vec::new(),
+        });
+        let client = mock_client(&url);
+        let events = client.connection_events().unwrap();
+
+        let _executions = client.subscribe_executions().unwrap();
+        let ticks = client.subscribe_ticks("005930").unwrap();
+        // 잘못된 frame 뒤의 데이터는 그대로 전달
+        let tick = ticks.recv_timeout(ACK_TIMEOUT).unwrap();
+        assert_eq!(tick.ticker, "005930");
+
+        for tr_id in ["H0STCNI9", "H0STCNT0"] {
+            assert!(matches!(
+                events.recv_timeout(ACK_TIMEOUT).unwrap(),
+                ConnectionEvent::Dropped { tr_id: id, .. } if id == tr_id
+            ));
+        }
+    }
+
+    #[test]
+    fn test_subscribe_while_pending() {
+        let requests = Requests::default();
+        let log = requests.clone();
+        let url = mock_listener(move |_, mut socket| {
+            // 첫 요청은 늦게 거절
+            let req = match socket.read_message() {
+                Ok(Message::Text(text)) => text,
+                _ => return,
+            };
+            log.lock()
+                .unwrap()
+                .push(("1".to_string(), req, String::new()));
+            thread::sleep(Duration::from_millis(300));
+            let nack = json!({
+                "header": {"tr_id": "H0STCNT0", "tr_key": "005930", "encrypt": "N"},
+                "body": {"rt_cd": "1", "msg_cd": "OPSP0011", "msg1": "invalid tr_key"}
+            });
+            let _ = socket.write_message(Message::Text(nack.to_string()));
+            while answer(&mut socket, &log).is_some() {}
+        });
+        let client = mock_client(&url);
+
+        let (first, second) = thread::scope(|s| {
+            let first = s.spawn(|| client.subscribe_ticks("005930"));
+            thread::sleep(Duration::from_millis(100));
+            let second = s.spawn(|| client.subscribe_ticks("005930"));
+            (first.join().unwrap(), second.join().unwrap())
+        });
+        // 응답을 기다리던 두 번째 구독도 같은 에러
+        assert!(matches!(first, Err(Error::Api { .. })));
+        assert!(matches!(second, Err(Error::Api { .. })));
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_resubscribe_rejected() {
+        let log = Requests::default();
+        let url = mock_listener(move |n, mut socket| {
+            if n == 0 {
+                answer(&mut socket, &log);
+                let _ = socket.close(None);
+                let _ = socket.write_pending();
+                return;
+            }
+            // 만료된 접속키로 재등록
+            if let Ok(Message::Text(_)) = socket.read_message() {
+                let nack = json!({
+                    "header": {"tr_id": "H0STCNT0", "tr_key": "005930", "encrypt": "N"},
+                    "body": {"rt_cd": "1", "msg_cd": "OPSP8996", "msg1": "invalid approval : NOT FOUND"}
+                });
+                let _ = socket.write_message(Message::Text(nack.to_string()));
+            }
+            while answer(&mut socket, &log).is_some() {}
+        });
+        let client = mock_client(&url);
+        let events = client.connection_events().unwrap();
+
+        let ticks = client.subscribe_ticks("005930").unwrap();
+        let received: Vec<_> = (0..4)
+            .map(|_| events.recv_timeout(ACK_TIMEOUT).unwrap())
+            .collect();
+        assert!(matches!(received[0], ConnectionEvent::Disconnected { .. }));
+        assert!(matches!(
+            received[1],
+            ConnectionEvent::Reconnecting { attempt: 1, .. }
+        ));
+        assert_eq!(received[2], ConnectionEvent::Connected);
+        assert!(matches!(
+            &received[3],
+            ConnectionEvent::Rejected { tr_id, tr_key, .. } if tr_id == "H0STCNT0" && tr_key == "005930"
+        ));
+        // 거절된 구독의 channel 은 닫힘
+        assert_eq!(
+            ticks.recv_timeout(ACK_TIMEOUT),
+            Err(RecvTimeoutError::Disconnected)
+        );
+    }
+
+    #[test]
+    fn test_unsupported_url() {
+        // 읽기 timeout 을 걸 수 없는 wss 는 거절
+        let session = mock_session("wss://ops.koreainvestment.com:21000", STALE_TIMEOUT);
+        assert!(matches!(
+            RealtimeClient::connect_to(session, "hts-id", false),
+            Err(Error::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_unsubscribe_ack_not_taken_by_subscribe() {
+        let log = Requests::default();
+        let url = mock_listener(move |_, mut socket| {
+            answer(&mut socket, &log);
+            let control = |msg1: &str, rt_cd: &str| {
+                json!({
+                    "header": {"tr_id": "H0STCNT0", "tr_key": "005930", "encrypt": "N"},
+                    "body": {"rt_cd": rt_cd, "msg_cd": "OPSP0000", "msg1": msg1}
+                })
+                .to_string()
+            };
+            // 해제 요청 뒤 다시 등록 요청이 온 다음에 해제 응답, 등록은 거절
+            for _ in 0..2 {
+                let _ = socket.read_message();
+            }
+            thread::sleep(Duration::from_millis(100));
+            let _ = socket.write_message(Message::Text(control("UNSUBSCRIBE SUCCESS", "0")));
+            thread::sleep(Duration::from_millis(100));
+            let _ = socket.write_message(Message::Text(control("MAX SUBSCRIBE OVER", "1")));
+            while answer(&mut socket, &log).is_some() {}
+        });
+        let client = mock_client(&url);
+
+        let _ticks = client.subscribe_ticks("005930").unwrap();
+        client.unsubscribe(Tick::TR_ID, "005930").unwrap();
+        assert!(matches!(
+            client.subscribe_ticks("005930"),
+            Err(Error::Api { .. })
+        ));
+    }
 
     #[test]
     #[ignore]
     fn test_ws_connect() {
-        let (tx, rx) = mpsc::channel::<String>();
-        let conf = load_account_config("./secret", false).unwrap();
+        let (tx, rx) = mpsc::channel::<RealtimeOrderBook>();
+        let kis = KisApi::new(load_account_config("./secret", false).unwrap());
 
-        let thread_join_handle = thread::spawn(move || {
-            // some work herewebsoket_test(&conf, "005935");
-            websoket_test(&conf, "005935", tx);
-        });
+        let thread_join_handle = thread::spawn(move || stream_order_book(&kis, "005935", tx));
 
         let received = rx.recv().unwrap();
+        assert_eq!(received.ticker, "005935");
+        drop(rx);
 
-        let res = thread_join_handle.join();
+        let _res = thread_join_handle.join();
     }
 }
@@ -1,8 +1,4 @@
 mod cli;
-use crossterm::{
-    event::{self, Event, KeyCode, KeyEvent},
-    terminal::{disable_raw_mode, enable_raw_mode},
-};
 
 fn main() {
     if let Err(e) = cli::get_args().and_then(cli::run) {
@@ -1,16 +1,16 @@
-use crate::kis::{self, api::KisApi};
+use crate::kis::{self, api::KisApi, KisResult};
 use reqwest::blocking;
 use std::collections::HashMap;
 use std::fs::File;
 
-type TradeResult<T> = Result<T, Box<dyn std::error::Error>>;
-
+// 전략 구현 전까지 사용하는 곳이 없음
+#[allow(dead_code)]
 trait Strategy {
     fn init_kis_api(&self, config: kis::AccountConfig) {
-        let kis = KisApi::new(config);
+        KisApi::new(config);
     }
     //BufReader::new(File::open(filename)?
-    fn get_tickers_from_csv(&self, csv_name: &str, column_name: &str) -> TradeResult<Vec<String>> {
+    fn get_tickers_from_csv(&self, csv_name: &str, column_name: &str) -> KisResult<Vec<String>> {
         let mut result = Vec::new();
         result.push(String::from("TEST"));
 
@@ -20,8 +20,8 @@ trait Strategy {
             .headers()?
             .iter()
             .position(|x| x == column_name);
-        let index = if let Some(n) = idx_of_ticker { n } else { 0 };
-        for header in csv.headers() {
+        let index = idx_of_ticker.unwrap_or_default();
+        if let Ok(header) = csv.headers() {
             println!("{:?}", header);
         }
         for record in csv.records() {
@@ -38,12 +38,13 @@ trait Strategy {
         riskmap
     }
 
-    fn make_list_stocks_to_buy_from_csv(&mut self) -> TradeResult<()>;
+    fn make_list_stocks_to_buy_from_csv(&mut self) -> KisResult<()>;
     fn calculate_order_price(&self, stock: &str) -> OrderPrice;
-    fn trade(&self) -> TradeResult<()>;
+    fn trade(&self) -> KisResult<()>;
     fn run();
 }
 
+#[allow(dead_code)]
 struct OrderPrice {
     ticker: String,
     buy: u32,
@@ -62,11 +63,12 @@ impl SimpleTrade {
         }
     }
 
+    #[allow(dead_code)]
     fn check_macro_signal(&self) {
         let client = reqwest::blocking::Client::new();
 
         let url = "http://127.0.0.1:8080/macro/status";
-        let res: blocking::Response = client
+        let _res: blocking::Response = client
             .get(url)
             // .headers(req.headers)
             // .query(&req.parameters)
@@ -75,8 +77,14 @@ impl SimpleTrade {
     }
 }
 
+impl Default for SimpleTrade {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Strategy for SimpleTrade {
-    fn make_list_stocks_to_buy_from_csv(&mut self) -> TradeResult<()> {
+    fn make_list_stocks_to_buy_from_csv(&mut self) -> KisResult<()> {
         let stock_list = self.get_tickers_from_csv("./data/all_latte_test.csv", "TICKER")?;
         for stock in stock_list.iter() {
             self.stock_order_list
@@ -94,7 +102,7 @@ impl Strategy for SimpleTrade {
         }
     }
 
-    fn trade(&self) -> TradeResult<()> {
+    fn trade(&self) -> KisResult<()> {
         Ok(())
     }
 
@@ -109,11 +117,12 @@ mod unit_test {
 
     use crate::kis::load_account_config;
 
-    // static TICKER: &'static str = "003490";
+    // static TICKER: &str = "003490";
+    #[allow(dead_code)]
     fn setup() -> KisApi {
-        let mut kis = KisApi::new(load_account_config("./secret", false).unwrap());
+        let kis = KisApi::new(load_account_config("./secret", false).unwrap());
         let res = kis.issue_access_token();
-        assert_eq!(res.unwrap(), true);
+        assert!(res.unwrap());
         kis
     }
 